@@ -0,0 +1,188 @@
+mod backdrop;
+mod vinyl;
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use iced::{Command, Size};
+
+use crate::{lyrics, player::Player, Lang, Message};
+
+pub use backdrop::Backdrop;
+pub use vinyl::{Label, Vinyl, LABEL_RADIUS};
+
+const TRACK_DURATION: Duration = Duration::from_millis(800);
+const LYRIC_DURATION: Duration = Duration::from_millis(400);
+const ICON_DURATION: Duration = Duration::from_millis(250);
+
+/// A value moving from `from` to `to` over `duration`, eased in and out.
+#[derive(Debug, Clone, Copy)]
+pub struct Tween {
+    from: f32,
+    to: f32,
+    start: Instant,
+    duration: Duration,
+}
+
+impl Tween {
+    pub fn new(from: f32, to: f32, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            start: Instant::now(),
+            duration,
+        }
+    }
+
+    /// A tween that already rests at `value`.
+    pub fn done(value: f32) -> Self {
+        Self::new(value, value, Duration::ZERO)
+    }
+
+    pub fn value(&self) -> f32 {
+        if !self.is_running() {
+            return self.to;
+        }
+
+        let t = self.start.elapsed().as_secs_f32() / self.duration.as_secs_f32();
+        let eased = if t < 0.5 {
+            4.0 * t * t * t
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+        };
+        self.from + (self.to - self.from) * eased
+    }
+
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.start.elapsed() < self.duration
+    }
+
+    /// Heads for `to`, starting from the current value so the motion stays continuous.
+    pub fn retarget(&mut self, to: f32, duration: Duration) {
+        *self = Self::new(self.value(), to, duration);
+    }
+}
+
+/// Animations of the iced front-end, started by [`Animations::sync`] when the
+/// player changes and advanced on every frame while any of them is running.
+pub struct Animations {
+    pub backdrop: Backdrop,
+    pub track: Tween,
+    /// Goes from 0 to 1 whenever the active lyric lines change.
+    pub lyric: Tween,
+    /// 0 for the "play" icon, 1 for the "pause" icon.
+    pub play_icon: Tween,
+    pub vinyl: Vinyl,
+    /// Source path of the current song, which stays the same when the playlist is reloaded.
+    current: String,
+    background: Option<String>,
+    avatar: Option<String>,
+    /// The language shown and how many lines of each track have started, which
+    /// change with the lines shown.
+    lyrics: (Lang, [usize; 2]),
+    last_frame: Instant,
+}
+
+impl Animations {
+    pub fn new(player: &Player) -> (Self, Command<Message>) {
+        let playing = if player.sink.is_paused() { 0.0 } else { 1.0 };
+        let song = &player.config[player.current_pos];
+
+        let animations = Self {
+            backdrop: Backdrop::new(song.background.clone()),
+            track: Tween::done(1.0),
+            lyric: Tween::done(1.0),
+            play_icon: Tween::done(playing),
+            vinyl: Vinyl::new(playing),
+            current: song.source_path.clone(),
+            background: song.background.clone(),
+            avatar: song.avatar.clone(),
+            lyrics: (player.lang, lyrics::started_lines(player)),
+            last_frame: Instant::now(),
+        };
+        let load = vinyl::load(&song.avatar);
+
+        (animations, load)
+    }
+
+    /// Starts the animations for whatever changed in `player` since the last call,
+    /// the background being blended for a window of `size`.
+    pub fn sync(&mut self, player: &Player, size: Size) -> Command<Message> {
+        let mut command = Command::none();
+        let song = &player.config[player.current_pos];
+
+        if song.source_path != self.current {
+            self.current = song.source_path.clone();
+            self.background = song.background.clone();
+            self.track = Tween::new(0.0, 1.0, TRACK_DURATION);
+            self.vinyl.switch_song();
+            self.avatar = song.avatar.clone();
+            command = Command::batch([
+                vinyl::load(&self.avatar),
+                self.backdrop.switch_song(self.background.clone(), size),
+            ]);
+        } else if song.avatar != self.avatar || song.background != self.background {
+            // the config was edited
+            self.background = song.background.clone();
+            self.backdrop.set(self.background.clone());
+            self.avatar = song.avatar.clone();
+            command = vinyl::load(&self.avatar);
+        }
+        self.backdrop.settle();
+
+        let lyrics = (player.lang, lyrics::started_lines(player));
+        if lyrics != self.lyrics {
+            self.lyrics = lyrics;
+            self.lyric = Tween::new(0.0, 1.0, LYRIC_DURATION);
+        }
+
+        let playing = if player.sink.is_paused() { 0.0 } else { 1.0 };
+        if self.play_icon.target() != playing {
+            self.play_icon.retarget(playing, ICON_DURATION);
+            self.vinyl.set_playing(playing);
+        }
+
+        command
+    }
+
+    /// Shows the vinyl loaded from `avatar` unless the song has changed meanwhile.
+    pub fn loaded(&mut self, avatar: Option<String>, label: Option<Arc<Label>>) {
+        if avatar == self.avatar {
+            self.vinyl.loaded(label, self.track.value());
+        }
+    }
+
+    /// Loads the avatar of the current song again, after its file changed.
+    pub fn reload_vinyl(&self) -> Command<Message> {
+        vinyl::load(&self.avatar)
+    }
+
+    /// Advances the animations that depend on the time between frames.
+    pub fn tick(&mut self, now: Instant) {
+        let delta = now.saturating_duration_since(self.last_frame);
+        self.last_frame = now;
+
+        // frames stop while nothing moves, so the first one after that would jump ahead
+        let delta = if delta < Duration::from_millis(100) {
+            delta
+        } else {
+            Duration::ZERO
+        };
+        self.vinyl.tick(delta, self.track.value());
+    }
+
+    /// Whether anything moves, the vinyl only counting while it is shown.
+    pub fn is_running(&self, vinyl_shown: bool) -> bool {
+        self.track.is_running()
+            || self.backdrop.is_running()
+            || self.lyric.is_running()
+            || self.play_icon.is_running()
+            || (vinyl_shown && self.vinyl.is_spinning())
+    }
+}
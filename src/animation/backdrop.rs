@@ -0,0 +1,125 @@
+use iced::{widget::image::Handle, Command, Size};
+use image::{imageops::FilterType, RgbaImage};
+
+use super::{Tween, TRACK_DURATION};
+use crate::Message;
+
+/// Frames blended from the previous background into the new one.
+const FRAMES: usize = 16;
+/// Longest side of the blended frames, which are only shown during the fade.
+const MAX_SIDE: f32 = 960.0;
+
+/// The background of the current song, which the one of the previous song fades
+/// into. Images cannot be drawn half transparent, so the fade is blended into a
+/// few frames when the song changes, and the previous background stays until then.
+pub struct Backdrop {
+    prev: Option<String>,
+    current: Option<String>,
+    frames: Vec<Handle>,
+    fade: Tween,
+    blending: bool,
+}
+
+impl Backdrop {
+    pub fn new(background: Option<String>) -> Self {
+        Self {
+            prev: None,
+            current: background,
+            frames: vec![],
+            fade: Tween::done(1.0),
+            blending: false,
+        }
+    }
+
+    /// Starts fading into `background`, blending the frames for a window of `size`.
+    pub fn switch_song(&mut self, background: Option<String>, size: Size) -> Command<Message> {
+        self.prev = std::mem::replace(&mut self.current, background);
+        self.frames.clear();
+        self.fade = Tween::done(1.0);
+        self.blending = self.prev != self.current;
+        if !self.blending {
+            return Command::none();
+        }
+
+        let (from, to) = (self.prev.clone(), self.current.clone());
+        let key = to.clone();
+        Command::perform(async move { blend(&from, &to, size) }, move |frames| {
+            Message::BackdropLoaded(key, frames)
+        })
+    }
+
+    /// Shows `background` right away, as the config was edited.
+    pub fn set(&mut self, background: Option<String>) {
+        self.current = background;
+        self.frames.clear();
+        self.blending = false;
+    }
+
+    pub fn loaded(&mut self, background: Option<String>, frames: Vec<Handle>) {
+        if self.blending && background == self.current {
+            self.blending = false;
+            self.frames = frames;
+            self.fade = Tween::new(0.0, 1.0, TRACK_DURATION);
+        }
+    }
+
+    /// Drops the blended frames once the fade is over.
+    pub fn settle(&mut self) {
+        if !self.frames.is_empty() && !self.fade.is_running() {
+            self.frames.clear();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.fade.is_running()
+    }
+
+    /// The blended frame to show, if the fade runs.
+    pub fn frame(&self) -> Option<Handle> {
+        if !self.fade.is_running() {
+            return None;
+        }
+        let step = (self.fade.value() * (FRAMES - 1) as f32).round() as usize;
+        self.frames.get(step).cloned()
+    }
+
+    /// The background to show when there is no blended frame.
+    pub fn background(&self) -> &Option<String> {
+        if self.blending {
+            &self.prev
+        } else {
+            &self.current
+        }
+    }
+}
+
+/// Blends `from` into `to`, both cropped to the shape of the window. A missing
+/// background fades to transparent, showing the window behind.
+fn blend(from: &Option<String>, to: &Option<String>, size: Size) -> Vec<Handle> {
+    let scale = (MAX_SIDE / size.width.max(size.height)).min(1.0);
+    let width = (size.width * scale).round().max(1.0) as u32;
+    let height = (size.height * scale).round().max(1.0) as u32;
+
+    let open = |path: &Option<String>| {
+        let image = image::open(path.as_ref()?).ok()?;
+        let image = image.resize_to_fill(width, height, FilterType::Triangle);
+        Some(image.into_rgba8().into_raw())
+    };
+    let blank = || RgbaImage::new(width, height).into_raw();
+    let (from, to) = match (open(from), open(to)) {
+        (None, None) => return vec![],
+        (from, to) => (from.unwrap_or_else(blank), to.unwrap_or_else(blank)),
+    };
+
+    (0..FRAMES)
+        .map(|step| {
+            let t = step as f32 / (FRAMES - 1) as f32;
+            let pixels = from
+                .iter()
+                .zip(&to)
+                .map(|(&a, &b)| (a as f32 + (b as f32 - a as f32) * t).round() as u8)
+                .collect::<Vec<_>>();
+            Handle::from_pixels(width, height, pixels)
+        })
+        .collect()
+}
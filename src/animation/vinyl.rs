@@ -0,0 +1,158 @@
+use std::{f32::consts::TAU, sync::Arc, time::Duration};
+
+use iced::{widget::image::Handle, Command};
+use image::{
+    imageops::{interpolate_bilinear, FilterType},
+    Rgba, RgbaImage,
+};
+
+use super::Tween;
+use crate::Message;
+
+/// Side of the rendered label, in pixels.
+const SIZE: u32 = 240;
+/// Part of the radius of the record covered by the avatar, the rest being the black vinyl.
+pub const LABEL_RADIUS: f32 = 0.72;
+/// Radius of the spindle hole, in parts of the radius of the label.
+const HOLE_RADIUS: f32 = 0.055;
+/// Labels rendered for each avatar, one per step of a turn.
+const TURN_STEPS: usize = 90;
+const TURNS_PER_SEC: f32 = 0.125;
+const EASE_DURATION: Duration = Duration::from_millis(1500);
+
+/// The avatar as the label of a spinning vinyl record, turned by `angle` like the
+/// record under it. The label is rendered once per step of a turn when the avatar
+/// is loaded, and only rendered on every frame while the avatar of the previous
+/// song fades into the new one.
+pub struct Vinyl {
+    prev: Option<Arc<Label>>,
+    current: Option<Arc<Label>>,
+    speed: Tween,
+    pub angle: f32,
+    fading: Option<Handle>,
+    /// The progress of the song change `fading` was rendered at.
+    rendered: f32,
+}
+
+/// An avatar cropped to a square, with the labels rendered from it for each step of a turn.
+#[derive(Debug)]
+pub struct Label {
+    source: RgbaImage,
+    turns: Vec<Handle>,
+}
+
+impl Vinyl {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            prev: None,
+            current: None,
+            speed: Tween::done(speed),
+            angle: 0.0,
+            fading: None,
+            rendered: 1.0,
+        }
+    }
+
+    /// Eases the rotation towards full speed (1) or a stop (0).
+    pub fn set_playing(&mut self, speed: f32) {
+        self.speed.retarget(speed, EASE_DURATION);
+    }
+
+    pub fn switch_song(&mut self) {
+        self.prev = self.current.take();
+    }
+
+    pub fn loaded(&mut self, label: Option<Arc<Label>>, track: f32) {
+        self.current = label;
+        self.render(track);
+    }
+
+    pub fn is_spinning(&self) -> bool {
+        self.speed.value() > 0.0
+    }
+
+    /// The label at the current angle.
+    pub fn label(&self) -> Option<Handle> {
+        if let Some(fading) = &self.fading {
+            return Some(fading.clone());
+        }
+        let label = self.current.as_ref()?;
+        let step = (self.angle / TAU * TURN_STEPS as f32).round() as usize % TURN_STEPS;
+        label.turns.get(step).cloned()
+    }
+
+    /// Turns the disc by the time elapsed since the last frame, `track` being the
+    /// progress of the song change during which the old label fades into the new one.
+    pub fn tick(&mut self, delta: Duration, track: f32) {
+        let turn = TAU * TURNS_PER_SEC * self.speed.value() * delta.as_secs_f32();
+        self.angle = (self.angle + turn) % TAU;
+        if track < 1.0 || track != self.rendered {
+            self.render(track);
+        }
+    }
+
+    fn render(&mut self, track: f32) {
+        let (label, alpha) = match &self.prev {
+            Some(_) if track < 0.5 => (&self.prev, 1.0 - track * 2.0),
+            Some(_) => (&self.current, track * 2.0 - 1.0),
+            None => (&self.current, track),
+        };
+
+        self.rendered = track;
+        self.fading = match label {
+            Some(label) if track < 1.0 => {
+                let pixels = render(&label.source, self.angle, alpha);
+                Some(Handle::from_pixels(SIZE, SIZE, pixels))
+            }
+            _ => None,
+        };
+    }
+}
+
+/// Loads `avatar` in the background, cropped to a square.
+pub fn load(avatar: &Option<String>) -> Command<Message> {
+    let avatar = avatar.clone();
+    let path = avatar.clone();
+
+    Command::perform(
+        async move {
+            let source = image::open(path?).ok()?;
+            let source = source.resize_to_fill(SIZE, SIZE, FilterType::Triangle);
+            let source = source.into_rgba8();
+            let turns = (0..TURN_STEPS)
+                .map(|step| TAU * step as f32 / TURN_STEPS as f32)
+                .map(|angle| Handle::from_pixels(SIZE, SIZE, render(&source, angle, 1.0)))
+                .collect();
+            Some(Arc::new(Label { source, turns }))
+        },
+        move |label| Message::VinylLoaded(avatar, label),
+    )
+}
+
+/// The avatar cut out as a round label with the spindle hole, turned by `angle`
+/// and faded by `alpha`.
+fn render(source: &RgbaImage, angle: f32, alpha: f32) -> Vec<u8> {
+    let center = SIZE as f32 / 2.0;
+    let edge = (SIZE - 1) as f32;
+    let (sin, cos) = angle.sin_cos();
+    let mut pixels = vec![0; (SIZE * SIZE * 4) as usize];
+
+    for (x, y) in (0..SIZE).flat_map(|y| (0..SIZE).map(move |x| (x, y))) {
+        let (dx, dy) = (x as f32 + 0.5 - center, y as f32 + 0.5 - center);
+        let radius = (dx * dx + dy * dy).sqrt() / center;
+        if !(HOLE_RADIUS..=1.0).contains(&radius) {
+            continue;
+        }
+
+        // the point of the avatar that the turn brings here
+        let sx = (cos * dx + sin * dy + center - 0.5).clamp(0.0, edge);
+        let sy = (cos * dy - sin * dx + center - 0.5).clamp(0.0, edge);
+        let Some(Rgba([r, g, b, a])) = interpolate_bilinear(source, sx, sy) else {
+            continue;
+        };
+        let idx = ((y * SIZE + x) * 4) as usize;
+        pixels[idx..idx + 4].copy_from_slice(&[r, g, b, (a as f32 * alpha) as u8]);
+    }
+
+    pixels
+}
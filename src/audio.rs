@@ -3,19 +3,31 @@ use rodio::{Decoder, OutputStream, Sink};
 use rustfft::{num_complex::Complex, FftPlanner};
 use std::{fs::File, io::BufReader, path::Path};
 
+use crate::i18n::{self, tr};
+
 pub type TheSource = Decoder<BufReader<File>>;
 
 pub fn new_sink() -> Sink {
-    let (stream, stream_handle) = OutputStream::try_default().unwrap();
+    let (stream, stream_handle) =
+        OutputStream::try_default().unwrap_or_else(|err| i18n::fail(tr().audio_device_failed, err));
     Box::leak(Box::new(stream));
-    Sink::try_new(&stream_handle).unwrap()
+    Sink::try_new(&stream_handle).unwrap_or_else(|err| i18n::fail(tr().audio_device_failed, err))
 }
 
 pub fn new_source(path: impl AsRef<Path>) -> TheSource {
+    try_new_source(path).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(1);
+    })
+}
+
+/// Like [`new_source`], but returns the error message instead of exiting.
+pub fn try_new_source(path: impl AsRef<Path>) -> Result<TheSource, String> {
     let path = path.as_ref();
-    let source = File::open(path).unwrap();
-    let source = BufReader::new(source);
-    Decoder::new(source).unwrap()
+    let source = File::open(path)
+        .map_err(|err| format!("{} {}: {err}", tr().open_audio_failed, path.display()))?;
+    Decoder::new(BufReader::new(source))
+        .map_err(|err| format!("{} {}: {err}", tr().decode_audio_failed, path.display()))
 }
 
 #[allow(unused)]
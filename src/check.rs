@@ -0,0 +1,202 @@
+use std::{collections::HashMap, fs, ops::Range, path::Path};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::{
+    audio,
+    config::{self, Config},
+    i18n::{self, tr},
+    lyrics,
+};
+
+#[derive(Deserialize)]
+struct Outer {
+    #[serde(default)]
+    music: Vec<Song>,
+}
+
+/// A `[[music]]` entry, keeping where each value is in the file.
+#[derive(Deserialize)]
+struct Song {
+    name: Spanned<String>,
+    source_path: Spanned<String>,
+    lyrics_first: Option<Spanned<String>>,
+    lyrics_second: Option<Spanned<String>>,
+    lyrics: Option<Spanned<String>>,
+    avatar: Option<Spanned<String>>,
+    background: Option<Spanned<String>>,
+    start: Option<Spanned<f32>>,
+    end: Option<Spanned<f32>>,
+    speed: Option<Spanned<f32>>,
+    gain: Option<Spanned<f32>>,
+}
+
+/// Checks the config file and every file its songs refer to, printing each problem
+/// with its line in the config file. Returns whether there were none.
+pub fn run(config_file: &Path) -> bool {
+    let problems = check(config_file);
+    for problem in &problems {
+        println!("{problem}");
+    }
+
+    if problems.is_empty() {
+        println!("{}", tr().config_ok);
+    } else {
+        println!("{}: {}", tr().problems_found, problems.len());
+    }
+    problems.is_empty()
+}
+
+fn check(config_file: &Path) -> Vec<String> {
+    let file = config_file.display();
+    let dir = config_file.parent().unwrap_or(Path::new(""));
+    let content = match fs::read_to_string(config_file) {
+        Ok(content) => content,
+        Err(err) => return vec![format!("{file}: {}: {err}", tr().read_config_failed)],
+    };
+    // the toml errors already tell the line they occurred at
+    match Config::parse(&content, dir) {
+        Ok((_, options)) => i18n::init(options.locale),
+        Err(err) => return vec![format!("{file}: {err}")],
+    }
+    let Outer { music } = match toml::from_str(&content) {
+        Ok(outer) => outer,
+        Err(err) => return vec![format!("{file}: {}: {err}", tr().parse_config_failed)],
+    };
+
+    let line = |span: Range<usize>| content[..span.start].matches('\n').count() + 1;
+    let mut problems = vec![];
+    let mut report = |span: Range<usize>, msg: String| {
+        problems.push(format!("{file}:{}: {msg}", line(span)));
+    };
+
+    let resolve = |value: &Spanned<String>| config::resolve(dir, value.get_ref());
+
+    let mut names = HashMap::new();
+    for song in &music {
+        let name = song.name.get_ref();
+        let this = line(song.name.span());
+        let first = *names.entry(name).or_insert(this);
+        if first != this {
+            report(
+                song.name.span(),
+                format!("{} \"{name}\" ({file}:{first})", tr().duplicate_name),
+            );
+        }
+
+        if let Err(err) = audio::try_new_source(resolve(&song.source_path)) {
+            report(song.source_path.span(), err);
+        }
+
+        if let (Some(start), Some(end)) = (&song.start, &song.end) {
+            if start.get_ref() >= end.get_ref() {
+                report(end.span(), tr().end_before_start.to_string());
+            }
+        }
+        if let Some(speed) = song
+            .speed
+            .as_ref()
+            .filter(|speed| !speed.get_ref().is_finite() || *speed.get_ref() <= 0.0)
+        {
+            report(speed.span(), tr().invalid_speed.to_string());
+        }
+        if let Some(gain) = song
+            .gain
+            .as_ref()
+            .filter(|gain| !gain.get_ref().is_finite())
+        {
+            report(gain.span(), tr().invalid_gain.to_string());
+        }
+
+        for lyric_file in [&song.lyrics_first, &song.lyrics_second, &song.lyrics]
+            .into_iter()
+            .flatten()
+        {
+            let path = &resolve(lyric_file);
+            match fs::read_to_string(path).map(|lyric| lyrics::validate(path, &lyric)) {
+                Ok(Ok(())) => (),
+                // the lines with a malformed timestamp, or none if no line has one
+                Ok(Err(lines)) => {
+                    let places = lines.iter().map(|line| format!(" {path}:{line}"));
+                    let places = places.collect::<String>();
+                    let places = if places.is_empty() {
+                        format!(" {path}")
+                    } else {
+                        places
+                    };
+                    report(
+                        lyric_file.span(),
+                        format!("{}{places}", tr().invalid_lyrics),
+                    );
+                }
+                Err(err) => report(
+                    lyric_file.span(),
+                    format!("{} {path}: {err}", tr().read_lyrics_failed),
+                ),
+            }
+        }
+
+        for picture in [&song.avatar, &song.background].into_iter().flatten() {
+            let path = &resolve(picture);
+            if let Err(err) = image::image_dimensions(path) {
+                report(
+                    picture.span(),
+                    format!("{} {path}: {err}", tr().read_image_failed),
+                );
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_reports_duplicate_names_and_invalid_values() {
+        let path =
+            std::env::temp_dir().join(format!("deci-player-{}-check.toml", std::process::id()));
+        let config = r#"
+[[music]]
+name = "a"
+source_path = "missing.mp3"
+start = 10
+end = 5
+
+[[music]]
+name = "a"
+source_path = "missing.mp3"
+speed = 0
+
+[[music]]
+name = "a"
+source_path = "missing.mp3"
+gain = inf
+"#;
+        fs::write(&path, config).unwrap();
+        let problems = check(&path);
+        fs::remove_file(&path).unwrap();
+
+        let file = path.display();
+        let found = |line: usize, msg: &str| {
+            problems
+                .iter()
+                .any(|problem| problem.starts_with(&format!("{file}:{line}: {msg}")))
+        };
+        // both duplicates point at the first song
+        assert!(found(
+            9,
+            &format!("{} \"a\" ({file}:3)", tr().duplicate_name)
+        ));
+        assert!(found(
+            14,
+            &format!("{} \"a\" ({file}:3)", tr().duplicate_name)
+        ));
+        assert!(found(6, tr().end_before_start));
+        assert!(found(11, tr().invalid_speed));
+        assert!(found(16, tr().invalid_gain));
+    }
+}
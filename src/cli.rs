@@ -0,0 +1,79 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, Subcommand};
+
+use crate::config;
+
+#[derive(Debug, Clone, Parser)]
+#[command(version, about = "A music-player written with rust + iced")]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Audio files or directories to play directly, instead of the songs in the config file
+    pub files: Vec<PathBuf>,
+
+    /// Path of the config file [default: ./config.toml, or deci-player/config.toml in the XDG config directories]
+    #[arg(short, long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Start paused
+    #[arg(long)]
+    pub paused: bool,
+
+    /// Index of the song to start with
+    #[arg(short, long, default_value_t = 0)]
+    pub index: usize,
+
+    /// Position to start the first song at, in seconds
+    #[arg(short, long, value_parser = parse_secs)]
+    pub start: Option<Duration>,
+
+    /// Initial volume, in percent
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub volume: Option<u8>,
+
+    /// Initial playback speed
+    #[arg(long, value_parser = parse_speed)]
+    pub speed: Option<f32>,
+
+    /// Play in the terminal without a window, controlled by commands read from stdin
+    #[arg(long)]
+    pub no_ui: bool,
+
+    /// Play in a terminal user interface instead of a window
+    #[arg(long, conflicts_with = "no_ui")]
+    pub tui: bool,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Check the config file and the files of its songs, exiting with an error if anything is wrong
+    CheckConfig,
+}
+
+impl Args {
+    /// The config file given on the command line, or else the default one.
+    pub fn config(&self) -> PathBuf {
+        match &self.config {
+            Some(path) => config::expand(&path.to_string_lossy()).into(),
+            None => config::default_path(),
+        }
+    }
+}
+
+fn parse_secs(s: &str) -> Result<Duration, String> {
+    s.parse::<f32>()
+        .ok()
+        .filter(|secs| *secs >= 0.0)
+        .map(Duration::from_secs_f32)
+        .ok_or_else(|| format!("invalid number of seconds: {s}"))
+}
+
+/// Accepts the speeds the player steps through.
+fn parse_speed(s: &str) -> Result<f32, String> {
+    s.parse::<f32>()
+        .ok()
+        .filter(|speed| [0.5, 1.0, 1.5, 2.0].contains(speed))
+        .ok_or_else(|| format!("invalid speed: {s}, expected 0.5, 1.0, 1.5 or 2.0"))
+}
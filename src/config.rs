@@ -1,28 +1,551 @@
 #![allow(unused)]
 
-use serde::Deserialize;
-use std::{fs, path::Path};
+use serde::{Deserialize, Deserializer};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    cli::Args,
+    i18n::{self, tr, Locale},
+    theme, Lang,
+};
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "wav", "ogg", "m4a", "aac"];
+const CONFIG_FILE: &str = "config.toml";
+const APP_DIR: &str = "deci-player";
 
 #[derive(Deserialize)]
 struct Outer {
-    #[serde(rename = "music")]
+    #[serde(rename = "music", default)]
     inner: Vec<Config>,
+    #[serde(flatten)]
+    options: Options,
+}
+
+/// Settings that apply to the whole player rather than to a single song.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Options {
+    /// Language of the interface, detected from the system when missing.
+    pub locale: Option<Locale>,
+    /// Name of a bundled theme or path to a theme file.
+    pub theme: Option<String>,
+    pub fonts: Fonts,
+    pub overlay: Overlay,
+    /// How the lyric offset adjusted while playing is saved to the lyrics files.
+    pub save_offset: SaveOffset,
+    /// Whether switching languages also shows the original lyrics in Latin letters.
+    pub romanization: bool,
+    pub normalize: Normalization,
+    pub export: Export,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SaveOffset {
+    /// Adds it to the `[offset:]` tag.
+    #[default]
+    Tag,
+    /// Shifts every timestamp by it.
+    Retime,
+}
+
+impl Options {
+    fn resolve(&mut self, dir: &Path) {
+        if let Some(theme) = &mut self.theme {
+            if !theme::is_bundled(theme) {
+                *theme = resolve(dir, theme);
+            }
+        }
+
+        let fonts = [
+            &mut self.fonts.ui,
+            &mut self.fonts.chinese,
+            &mut self.fonts.japanese,
+        ];
+        for path in fonts
+            .into_iter()
+            .flatten()
+            .filter_map(|font| font.path.as_mut())
+        {
+            *path = resolve(dir, path);
+        }
+        if let Some(file) = &mut self.export.file {
+            *file = resolve(dir, file);
+        }
+    }
+}
+
+/// The `[fonts]` table, with the font of the interface and the lyric fonts of each
+/// language. System fonts are used for the ones left out.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Fonts {
+    pub ui: Option<FontSource>,
+    pub chinese: Option<FontSource>,
+    pub japanese: Option<FontSource>,
+}
+
+/// The `[normalize]` table, with the rules applied to the lines of the lyrics of each
+/// language once they are read.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Normalization {
+    pub chinese: Normalize,
+    pub japanese: Normalize,
+}
+
+impl Normalization {
+    /// The rules of the lyrics of `lang`.
+    pub fn track(&self, lang: Lang) -> &Normalize {
+        match lang {
+            Lang::Japanese => &self.japanese,
+            _ => &self.chinese,
+        }
+    }
+}
+
+/// The normalization of a lyric track, applied in the order of the fields.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Normalize {
+    /// Whether credit lines like `作词：...` or `[by:...]` are left out.
+    pub strip_credits: bool,
+    /// Strings replaced by other ones, in order.
+    pub replace: Vec<(String, String)>,
+    /// Full-width or half-width forms of the ASCII characters.
+    pub width: Option<Width>,
+    /// Simplified or Traditional Chinese characters. Converting to Traditional is
+    /// approximate, as it cannot tell which of several forms a character stands for.
+    pub script: Option<Script>,
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Self {
+            strip_credits: false,
+            replace: vec![("，".into(), " ".into()), ("。".into(), " ".into())],
+            width: None,
+            script: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Width {
+    Half,
+    Full,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Script {
+    Simplified,
+    Traditional,
+}
+
+/// The `[export]` table, publishing the song playing and its lyric lines for
+/// streaming overlays.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Export {
+    /// A text file rewritten with the title, the artist and the lines whenever they change.
+    pub file: Option<String>,
+    /// An address like `127.0.0.1:8765` to serve the overlay page and the lines from.
+    pub address: Option<String>,
 }
 
+/// A font family, loaded from `path` or else looked up among the installed fonts.
 #[derive(Deserialize)]
+pub struct FontSource {
+    pub family: String,
+    pub path: Option<String>,
+}
+
+/// The `[overlay]` table, configuring the desktop lyrics window.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Overlay {
+    pub width: f32,
+    pub height: f32,
+    pub font_size: u16,
+    pub color: String,
+    pub outline_color: String,
+    pub outline_width: f32,
+    pub locked: bool,
+}
+
+impl Default for Overlay {
+    fn default() -> Self {
+        Self {
+            width: 1000.0,
+            height: 140.0,
+            font_size: 36,
+            color: "#ffffff".into(),
+            outline_color: "#000000".into(),
+            outline_width: 2.0,
+            locked: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
 pub struct Config {
     pub name: String,
+    pub artist: Option<String>,
     pub source_path: String,
     pub lyrics_first: Option<String>,
     pub lyrics_second: Option<String>,
-    pub avatar: String,
-    pub background: String,
+    /// A lyrics file with both the original and the translation, used for the
+    /// languages without a file of their own.
+    pub lyrics: Option<String>,
+    pub avatar: Option<String>,
+    pub background: Option<String>,
+    /// Where the song starts playing, in seconds.
+    #[serde(default, deserialize_with = "secs")]
+    pub start: Option<Duration>,
+    /// Where the song stops playing, in seconds.
+    #[serde(default, deserialize_with = "secs")]
+    pub end: Option<Duration>,
+    /// Gain applied to the song, in dB.
+    #[serde(default)]
+    pub gain: f32,
+    /// Playback speed set when the song starts, kept from the previous song when missing.
+    pub speed: Option<f32>,
+    /// Offsets of the lyric tracks in milliseconds, like the `[offset:]` tag of LRC
+    /// files: positive values show the lyrics sooner.
+    #[serde(default)]
+    pub lyrics_first_offset: i64,
+    #[serde(default)]
+    pub lyrics_second_offset: i64,
+    /// Lyrics shown when the song starts: "all", "chinese", "japanese" or "romanized".
+    #[serde(default, deserialize_with = "shown_lang")]
+    pub lang: Option<Lang>,
+    /// The language of the original lyrics, which the translation is aligned to when
+    /// both are shown: "chinese" or "japanese" (the default).
+    #[serde(default, deserialize_with = "track_lang")]
+    pub original: Option<Lang>,
+}
+
+/// The values of `lang`, which leave out the languages the player has no lyrics for.
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ShownLang {
+    All,
+    Chinese,
+    Japanese,
+    Romanized,
+}
+
+/// The values of `original`, the languages with lyrics files of their own.
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TrackLang {
+    Chinese,
+    Japanese,
 }
 
 impl Config {
-    pub fn new(config_file: impl AsRef<Path>) -> Vec<Self> {
-        let content = fs::read_to_string(config_file.as_ref()).unwrap();
-        let Outer { inner } = toml::from_str(&content).unwrap();
-        inner
+    /// Loads the playlist and the options selected by the command line arguments.
+    pub fn load(args: &Args) -> (Vec<Self>, Options) {
+        let config = args.config();
+        // the options still come from the config file when playing files directly
+        if args.files.is_empty() {
+            Config::new(&config)
+        } else if config.exists() {
+            (Config::from_paths(&args.files), Config::new(&config).1)
+        } else {
+            (Config::from_paths(&args.files), Options::default())
+        }
+    }
+
+    pub fn new(config_file: impl AsRef<Path>) -> (Vec<Self>, Options) {
+        Self::try_new(config_file).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            std::process::exit(1);
+        })
+    }
+
+    /// Like [`Config::new`], but returns the error message instead of exiting.
+    pub fn try_new(config_file: impl AsRef<Path>) -> Result<(Vec<Self>, Options), String> {
+        let config_file = config_file.as_ref();
+        let content = fs::read_to_string(config_file)
+            .map_err(|err| format!("{}: {err}", tr().read_config_failed))?;
+        Self::parse(&content, config_file.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Parses the `content` of a config file, resolving its paths against `dir`.
+    pub fn parse(content: &str, dir: &Path) -> Result<(Vec<Self>, Options), String> {
+        let Outer {
+            mut inner,
+            mut options,
+        } = toml::from_str(content)
+            .map_err(|err| format!("{}: {err}", tr().parse_config_failed))?;
+
+        for song in &mut inner {
+            song.resolve(dir);
+        }
+        options.resolve(dir);
+        Ok((inner, options))
+    }
+
+    /// The lyrics file of `lang` and its offset.
+    pub fn lyrics(&self, lang: Lang) -> (Option<&String>, i64) {
+        match lang {
+            Lang::Chinese => (self.lyrics_first.as_ref(), self.lyrics_first_offset),
+            Lang::Japanese => (self.lyrics_second.as_ref(), self.lyrics_second_offset),
+            Lang::All | Lang::Romanized | Lang::English => (None, 0),
+        }
+    }
+
+    /// The file the lyrics of `lang` are read from, and whether it is the combined
+    /// `lyrics` file they are only a part of.
+    pub fn lyrics_file(&self, lang: Lang) -> Option<(&String, bool)> {
+        match (self.lyrics(lang).0, lang) {
+            (Some(path), _) => Some((path, false)),
+            (None, Lang::Chinese | Lang::Japanese) => Some((self.lyrics.as_ref()?, true)),
+            (None, _) => None,
+        }
+    }
+
+    /// The language of the original lyrics.
+    pub fn original(&self) -> Lang {
+        self.original.unwrap_or(Lang::Japanese)
+    }
+
+    /// The factor of the gain of the song.
+    pub fn amplitude(&self) -> f32 {
+        10f32.powf(self.gain / 20.0)
+    }
+
+    fn resolve(&mut self, dir: &Path) {
+        self.source_path = resolve(dir, &self.source_path);
+        let paths = [
+            &mut self.lyrics_first,
+            &mut self.lyrics_second,
+            &mut self.lyrics,
+            &mut self.avatar,
+            &mut self.background,
+        ];
+        for path in paths.into_iter().flatten() {
+            *path = resolve(dir, path);
+        }
+    }
+
+    /// Builds a playlist from audio files, expanding directories into the audio files they contain.
+    pub fn from_paths(paths: &[PathBuf]) -> Vec<Self> {
+        let mut files = vec![];
+        for path in paths {
+            if path.is_dir() {
+                let mut entries = fs::read_dir(path)
+                    .unwrap_or_else(|err| i18n::fail(tr().read_dir_failed, err))
+                    .map(|entry| entry.unwrap().path())
+                    .filter(|path| is_audio(path))
+                    .collect::<Vec<_>>();
+                entries.sort();
+                files.extend(entries);
+            } else if is_audio(path) {
+                files.push(path.clone());
+            }
+        }
+
+        files.into_iter().map(Self::from_file).collect()
+    }
+
+    fn from_file(path: PathBuf) -> Self {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self {
+            name,
+            source_path: path.to_string_lossy().into_owned(),
+            ..Self::default()
+        }
+    }
+}
+
+fn secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    let secs = f32::deserialize(deserializer)?;
+    Duration::try_from_secs_f32(secs)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+fn shown_lang<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Lang>, D::Error> {
+    let lang = match ShownLang::deserialize(deserializer)? {
+        ShownLang::All => Lang::All,
+        ShownLang::Chinese => Lang::Chinese,
+        ShownLang::Japanese => Lang::Japanese,
+        ShownLang::Romanized => Lang::Romanized,
+    };
+    Ok(Some(lang))
+}
+
+fn track_lang<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Lang>, D::Error> {
+    let lang = match TrackLang::deserialize(deserializer)? {
+        TrackLang::Chinese => Lang::Chinese,
+        TrackLang::Japanese => Lang::Japanese,
+    };
+    Ok(Some(lang))
+}
+
+fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Adds `key = path` to the `[[music]]` entry of the song at `source_path`, right
+/// after its `source_path`, leaving the rest of the config file as it is.
+pub fn add_lyrics(config_file: &Path, source_path: &str, key: &str, path: &str) -> io::Result<()> {
+    #[derive(Deserialize)]
+    struct Outer {
+        #[serde(default)]
+        music: Vec<Song>,
+    }
+    #[derive(Deserialize)]
+    struct Song {
+        source_path: toml::Spanned<String>,
+    }
+
+    let mut content = fs::read_to_string(config_file)?;
+    let Outer { music } =
+        toml::from_str(&content).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let dir = config_file.parent().unwrap_or(Path::new(""));
+    let Some(song) = music
+        .iter()
+        .find(|song| resolve(dir, song.source_path.get_ref()) == source_path)
+    else {
+        return Ok(());
+    };
+    let end = song.source_path.span().end;
+    let end = content[end..]
+        .find('\n')
+        .map_or(content.len(), |idx| end + idx);
+
+    // written relative to the config file like the other paths
+    let path = Path::new(path).strip_prefix(dir).unwrap_or(Path::new(path));
+    let value = toml::Value::String(path.to_string_lossy().into_owned());
+    content.insert_str(end, &format!("\n{key} = {value}"));
+    fs::write(config_file, content)
+}
+
+/// The config file used when none is given: `./config.toml` if there is one, or else
+/// the first `deci-player/config.toml` found in the XDG config directories.
+pub fn default_path() -> PathBuf {
+    let local = PathBuf::from(CONFIG_FILE);
+    if local.exists() {
+        return local;
+    }
+
+    let home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+    let dirs = env::var("XDG_CONFIG_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/etc/xdg".into());
+
+    home.into_iter()
+        .chain(env::split_paths(&dirs))
+        .map(|dir| dir.join(APP_DIR).join(CONFIG_FILE))
+        .find(|path| path.exists())
+        .unwrap_or(local)
+}
+
+/// Expands `path` and makes it relative to `dir`, the directory of the config file,
+/// unless it is absolute.
+pub fn resolve(dir: &Path, path: &str) -> String {
+    let path = expand(path);
+    if Path::new(&path).is_absolute() {
+        path
+    } else {
+        dir.join(path).to_string_lossy().into_owned()
+    }
+}
+
+/// Expands a leading `~` to the home directory, and `$VAR` or `${VAR}` to the value
+/// of the environment variable, leaving the unset ones as they are.
+pub fn expand(path: &str) -> String {
+    let mut path = path.to_string();
+    if path == "~" || path.starts_with("~/") {
+        if let Some(home) = env::var_os("HOME") {
+            path.replace_range(..1, &home.to_string_lossy());
+        }
+    }
+
+    let mut result = String::new();
+    let mut rest = path.as_str();
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let (name, len) = match rest.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                (&rest[..end], end)
+            }
+        };
+
+        match env::var(name) {
+            Ok(value) if !name.is_empty() => result.push_str(&value),
+            _ => result.push_str(&format!("${}", &rest[..len])),
+        }
+        rest = &rest[len..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_reads_the_home_directory() {
+        let home = env::var("HOME").unwrap();
+        assert_eq!(expand("~/music"), format!("{home}/music"));
+        assert_eq!(expand("~"), home);
+        assert_eq!(expand("a/~/b"), "a/~/b");
+    }
+
+    #[test]
+    fn expand_reads_environment_variables() {
+        env::set_var("DECI_PLAYER_TEST_DIR", "/music");
+        assert_eq!(expand("$DECI_PLAYER_TEST_DIR/a.mp3"), "/music/a.mp3");
+        assert_eq!(expand("${DECI_PLAYER_TEST_DIR}a.mp3"), "/musica.mp3");
+        // unset or unfinished ones are left as they are
+        assert_eq!(
+            expand("$DECI_PLAYER_TEST_UNSET/a"),
+            "$DECI_PLAYER_TEST_UNSET/a"
+        );
+        assert_eq!(expand("${DECI_PLAYER_TEST_DIR"), "${DECI_PLAYER_TEST_DIR");
+        assert_eq!(expand("a$"), "a$");
+    }
+
+    #[test]
+    fn resolve_keeps_absolute_paths() {
+        let dir = Path::new("/config");
+        assert_eq!(resolve(dir, "songs/a.mp3"), "/config/songs/a.mp3");
+        assert_eq!(resolve(dir, "/songs/a.mp3"), "/songs/a.mp3");
+        env::set_var("DECI_PLAYER_TEST_SONGS", "/songs");
+        assert_eq!(
+            resolve(dir, "${DECI_PLAYER_TEST_SONGS}/a.mp3"),
+            "/songs/a.mp3"
+        );
     }
 }
@@ -0,0 +1,128 @@
+use std::{
+    fs, iter,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{config::Config, i18n::tr, lyrics, Lang};
+
+/// Tap-to-sync editor of a lyric track: the playback time is stamped onto the lines
+/// of a plain text file one after another, and the stamped lines saved as an LRC file.
+pub struct LyricEditor {
+    pub lang: Lang,
+    pub lines: Vec<String>,
+    /// The times of the lines stamped so far, which are the first ones.
+    pub times: Vec<Duration>,
+    /// The LRC file to save, which is the lyrics file of the track if it has one.
+    pub path: PathBuf,
+    /// The result of the last save, or the reason there are no lines to stamp.
+    pub message: Option<String>,
+}
+
+impl LyricEditor {
+    /// Opens the track of `lang` of `song`, reading the lines from the `.txt` file next
+    /// to its lyrics file, or else from the lyrics file itself so it can be synced
+    /// again. Lyrics in another format than LRC are saved to an LRC file next to them.
+    pub fn open(song: &Config, lang: Lang) -> Self {
+        let source = match song.lyrics(lang).0 {
+            Some(path) => PathBuf::from(path),
+            None => default_path(song, lang),
+        };
+        let path = match source.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("lrc") => source.clone(),
+            _ => source.with_extension("lrc"),
+        };
+        let text = source.with_extension("txt");
+
+        let read = |path: &Path| {
+            let content = fs::read_to_string(path).ok()?;
+            Some(lyrics::text_lines(&path.to_string_lossy(), &content))
+        };
+        let (lines, message) = match read(&text).or_else(|| read(&source)) {
+            Some(lines) => (lines, None),
+            None => (
+                vec![],
+                Some(format!("{} {}", tr().lyrics_text_missing, text.display())),
+            ),
+        };
+
+        Self {
+            lang,
+            lines,
+            times: vec![],
+            path,
+            message,
+        }
+    }
+
+    /// The index of the next line to stamp.
+    pub fn current(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Stamps `time` onto the next line.
+    pub fn stamp(&mut self, time: Duration) {
+        if self.current() < self.lines.len() {
+            self.times.push(time);
+        }
+    }
+
+    /// Removes the last stamp, returning the time of the stamp before it to replay
+    /// the unstamped line from.
+    pub fn undo(&mut self) -> Option<Duration> {
+        self.times.pop();
+        self.times.last().copied()
+    }
+
+    /// Moves the last stamp by `ms` milliseconds.
+    pub fn nudge(&mut self, ms: i64) {
+        // the stamps stay in order
+        let prev = self.times.iter().rev().nth(1).copied().unwrap_or_default();
+        if let Some(time) = self.times.last_mut() {
+            let nudged = (time.as_millis() as i64 + ms).max(0) as u64;
+            *time = Duration::from_millis(nudged).max(prev);
+        }
+    }
+
+    /// The time to play the last stamped line again from.
+    pub fn replay_time(&self) -> Option<Duration> {
+        self.times.last().copied()
+    }
+
+    /// Writes the lines to the LRC file, returning whether it was written. The lines
+    /// not stamped yet are kept without a timestamp, so players skip them but the
+    /// editor reads them again.
+    pub fn save(&mut self) -> bool {
+        if self.times.is_empty() {
+            return false;
+        }
+        let times = self
+            .times
+            .iter()
+            .map(|time| lyrics::format_time(time.as_secs_f32()));
+        let content = self
+            .lines
+            .iter()
+            .zip(times.chain(iter::repeat(String::new())))
+            .map(|(line, time)| format!("{time}{line}\n"))
+            .collect::<String>();
+
+        let result = fs::write(&self.path, content);
+        self.message = Some(match &result {
+            Ok(()) => format!("{} {}", tr().saved_to, self.path.display()),
+            Err(err) => format!("{} {}: {err}", tr().save_lyrics_failed, self.path.display()),
+        });
+        result.is_ok()
+    }
+}
+
+/// The LRC file of a track without one, named after the audio file and the language.
+fn default_path(song: &Config, lang: Lang) -> PathBuf {
+    let source = Path::new(&song.source_path);
+    let suffix = match lang {
+        Lang::Japanese => "ja",
+        _ => "zh",
+    };
+    let name = source.file_stem().unwrap_or_default().to_string_lossy();
+    source.with_file_name(format!("{name}.{suffix}.lrc"))
+}
@@ -0,0 +1,234 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use crate::i18n::tr;
+
+/// The page for the browser sources of streaming software.
+const OVERLAY_PAGE: &str = r#"<!DOCTYPE html>
+<meta charset="utf-8">
+<title>deci-player</title>
+<style>
+  body { margin: 0; color: #fff; font: bold 36px sans-serif; text-align: center; text-shadow: 0 0 4px #000; }
+  #song { font-size: 20px; }
+</style>
+<div id="song"></div>
+<div id="lines"></div>
+<script>
+  new EventSource("/events").onmessage = (event) => {
+    const now = JSON.parse(event.data);
+    document.getElementById("song").textContent = now.artist ? `${now.title} - ${now.artist}` : now.title;
+    const lines = now.lines.map((line) => Object.assign(document.createElement("div"), { textContent: line }));
+    document.getElementById("lines").replaceChildren(...lines);
+  };
+</script>
+"#;
+
+#[derive(Clone, PartialEq, Default)]
+pub struct NowPlaying {
+    pub title: String,
+    pub artist: String,
+    pub lines: Vec<String>,
+}
+
+impl NowPlaying {
+    fn text(&self) -> String {
+        let mut text = format!("{}\n{}\n", self.title, self.artist);
+        for line in &self.lines {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+
+    fn json(&self) -> String {
+        let lines = self.lines.iter().map(|line| json_string(line));
+        format!(
+            r#"{{"title":{},"artist":{},"lines":[{}]}}"#,
+            json_string(&self.title),
+            json_string(&self.artist),
+            lines.collect::<Vec<_>>().join(","),
+        )
+    }
+}
+
+/// Publishes what is playing to a text file and a local HTTP server.
+#[derive(Default)]
+pub struct Exporter {
+    last: Option<NowPlaying>,
+    server: Option<Server>,
+}
+
+impl Exporter {
+    /// Goes on without the server if `address` cannot be bound, returning the error.
+    pub fn new(address: Option<&str>) -> (Self, Option<String>) {
+        let mut error = None;
+        let server = address.and_then(|address| match Server::start(address) {
+            Ok(server) => Some(server),
+            Err(err) => {
+                error = Some(format!("{} {address}: {err}", tr().export_failed));
+                None
+            }
+        });
+        (Self { last: None, server }, error)
+    }
+
+    pub fn is_serving(&self) -> bool {
+        self.server.is_some()
+    }
+
+    pub fn publish(&mut self, now: NowPlaying, file: Option<&str>) -> Result<(), String> {
+        if self.last.as_ref() == Some(&now) {
+            return Ok(());
+        }
+        if let Some(server) = &self.server {
+            server.publish(now.json());
+        }
+        let written = match file {
+            Some(file) => write_atomically(Path::new(file), &now.text())
+                .map_err(|err| format!("{} {file}: {err}", tr().export_failed)),
+            None => Ok(()),
+        };
+        self.last = Some(now);
+        written
+    }
+}
+
+/// Writes through a renamed temporary file, so readers never see half a file.
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, content)?;
+    fs::rename(&temp, path)
+}
+
+/// Serves the page at `/`, the JSON of what is playing at `/now` and its changes
+/// as server-sent events at `/events`.
+struct Server {
+    updates: Sender<Update>,
+}
+
+enum Update {
+    Now(String),
+    Get(TcpStream),
+    Subscribe(TcpStream),
+}
+
+impl Server {
+    fn start(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let (updates, received) = mpsc::channel();
+
+        thread::spawn(move || broadcast(received));
+        let requests = updates.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // a client that is slow to send its request only holds up its own thread
+                let requests = requests.clone();
+                thread::spawn(move || handle(stream, &requests));
+            }
+        });
+        Ok(Self { updates })
+    }
+
+    fn publish(&self, json: String) {
+        // the thread writing to the clients runs as long as the one accepting them
+        let _ = self.updates.send(Update::Now(json));
+    }
+}
+
+fn broadcast(updates: Receiver<Update>) {
+    let mut current = NowPlaying::default().json();
+    let mut clients: Vec<TcpStream> = vec![];
+    for update in updates {
+        match update {
+            Update::Now(json) => {
+                let event = event(&json);
+                clients.retain_mut(|client| client.write_all(event.as_bytes()).is_ok());
+                current = json;
+            }
+            Update::Get(mut stream) => {
+                let _ = respond(&mut stream, "200 OK", "application/json", &current);
+            }
+            Update::Subscribe(mut stream) => {
+                if stream.write_all(event(&current).as_bytes()).is_ok() {
+                    clients.push(stream);
+                }
+            }
+        }
+    }
+}
+
+fn handle(mut stream: TcpStream, updates: &Sender<Update>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // the headers are read so closing the connection does not reset it
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let path = path.split('?').next().unwrap_or(path);
+    match path {
+        "/" => respond(&mut stream, "200 OK", "text/html", OVERLAY_PAGE),
+        "/now" => {
+            let _ = updates.send(Update::Get(stream));
+            Ok(())
+        }
+        "/events" => {
+            stream.write_all(
+                b"HTTP/1.1 200 OK\r\n\
+                Content-Type: text/event-stream\r\n\
+                Cache-Control: no-cache\r\n\
+                Access-Control-Allow-Origin: *\r\n\r\n",
+            )?;
+            let _ = updates.send(Update::Subscribe(stream));
+            Ok(())
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", "Not Found"),
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+        Content-Type: {content_type}; charset=utf-8\r\n\
+        Content-Length: {}\r\n\
+        Access-Control-Allow-Origin: *\r\n\
+        Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn event(json: &str) -> String {
+    format!("data: {json}\n\n")
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -0,0 +1,81 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    fs,
+    sync::{Mutex, PoisonError},
+};
+
+use iced::Font;
+
+use crate::{
+    config::{FontSource, Fonts},
+    i18n::tr,
+    Lang,
+};
+
+/// The family names fonts were looked up by, kept for the whole run as iced needs
+/// them to outlive the application.
+static FAMILIES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// The fonts of the iced front-end, resolved from the `[fonts]` table.
+#[derive(Debug, Clone, Copy)]
+pub struct FontSet {
+    pub ui: Font,
+    pub chinese: Font,
+    pub japanese: Font,
+}
+
+impl FontSet {
+    pub fn new(fonts: &Fonts) -> Self {
+        let ui = font(&fonts.ui).unwrap_or(Font::DEFAULT);
+
+        Self {
+            ui,
+            chinese: font(&fonts.chinese).unwrap_or(ui),
+            japanese: font(&fonts.japanese).unwrap_or(ui),
+        }
+    }
+
+    /// The font of the lyrics in `lang`.
+    pub fn lyric(&self, lang: Lang) -> Font {
+        match lang {
+            Lang::Chinese => self.chinese,
+            Lang::Japanese => self.japanese,
+            _ => self.ui,
+        }
+    }
+}
+
+/// Reads the font files of the `[fonts]` table, skipping the ones that cannot be
+/// read so their family falls back to the system fonts.
+pub fn read_files(fonts: &Fonts) -> Vec<Cow<'static, [u8]>> {
+    [&fonts.ui, &fonts.chinese, &fonts.japanese]
+        .into_iter()
+        .flatten()
+        .filter_map(|source| source.path.as_ref())
+        .filter_map(|path| match fs::read(path) {
+            Ok(bytes) => Some(bytes.into()),
+            Err(err) => {
+                eprintln!("{} {path}: {err}", tr().load_font_failed);
+                None
+            }
+        })
+        .collect()
+}
+
+fn font(source: &Option<FontSource>) -> Option<Font> {
+    Some(Font::with_name(intern(&source.as_ref()?.family)))
+}
+
+/// Leaks each family name once, however often the fonts are resolved.
+fn intern(family: &str) -> &'static str {
+    let mut families = FAMILIES.lock().unwrap_or_else(PoisonError::into_inner);
+    match families.get(family) {
+        Some(family) => family,
+        None => {
+            let family = Box::leak(family.to_owned().into_boxed_str());
+            families.insert(family);
+            family
+        }
+    }
+}
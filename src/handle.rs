@@ -10,8 +10,12 @@ pub fn handle_key(mode: ViewMode, key: Key<SmolStr>, modifiers: Modifiers) -> Me
 
     match mode {
         ViewMode::Play => handle_in_normal(key, modifiers),
+        ViewMode::Mini => handle_in_mini(key, modifiers),
+        ViewMode::Fullscreen => handle_in_fullscreen(key, modifiers),
         ViewMode::Help => handle_in_help(key, modifiers),
         ViewMode::ConfirmQuit => handle_in_confirm_quit(key, modifiers),
+        ViewMode::LyricEditor => handle_in_lyric_editor(key, modifiers),
+        ViewMode::Search => handle_in_search(key, modifiers),
     }
 }
 
@@ -35,6 +39,17 @@ fn handle_in_normal(key: Key<&str>, _modifiers: Modifiers) -> Message {
             "p" => Message::TogglePlay,
             "t" => Message::ToggleLang,
             "s" => Message::ToggleSpeed,
+            "m" => Message::SwitchView(ViewMode::Mini),
+            "f" => Message::SwitchView(ViewMode::Fullscreen),
+            "o" => Message::ToggleOverlay,
+            "l" => Message::ToggleOverlayLock,
+            "c" => Message::NextTheme,
+            "[" => Message::ShiftLyrics(-100),
+            "]" => Message::ShiftLyrics(100),
+            "w" => Message::SaveLyricOffset,
+            "r" => Message::ToggleReadings,
+            "e" => Message::SwitchView(ViewMode::LyricEditor),
+            "/" => Message::SwitchView(ViewMode::Search),
             _ => Message::Nothing,
         }
     } else if let Key::Named(n) = key {
@@ -51,6 +66,30 @@ fn handle_in_normal(key: Key<&str>, _modifiers: Modifiers) -> Message {
     }
 }
 
+fn handle_in_mini(key: Key<&str>, modifiers: Modifiers) -> Message {
+    if let Key::Character("m") = key {
+        return Message::SwitchView(ViewMode::Play);
+    }
+
+    // the mini window only leaves for the views that take the whole window back
+    match handle_in_normal(key, modifiers) {
+        msg @ Message::SwitchView(ViewMode::Help | ViewMode::ConfirmQuit) => msg,
+        Message::SwitchView(_) => Message::Nothing,
+        msg => msg,
+    }
+}
+
+fn handle_in_fullscreen(key: Key<&str>, modifiers: Modifiers) -> Message {
+    if let Key::Character("f") | Key::Named(Named::Escape) = key {
+        return Message::SwitchView(ViewMode::Play);
+    }
+
+    match handle_in_normal(key, modifiers) {
+        Message::SwitchView(_) => Message::Nothing,
+        msg => msg,
+    }
+}
+
 fn handle_in_help(key: Key<&str>, _modifiers: Modifiers) -> Message {
     if let Key::Character(c) = key {
         match c {
@@ -61,3 +100,43 @@ fn handle_in_help(key: Key<&str>, _modifiers: Modifiers) -> Message {
         Message::Nothing
     }
 }
+
+fn handle_in_lyric_editor(key: Key<&str>, _modifiers: Modifiers) -> Message {
+    if let Key::Character(c) = key {
+        match c {
+            "e" => Message::SwitchView(ViewMode::Play),
+            "p" => Message::TogglePlay,
+            "t" => Message::ToggleLang,
+            "r" => Message::ReplayLine,
+            "[" => Message::NudgeStamp(-100),
+            "]" => Message::NudgeStamp(100),
+            "w" => Message::SaveEditedLyrics,
+            _ => Message::Nothing,
+        }
+    } else if let Key::Named(n) = key {
+        match n {
+            Named::Space | Named::Enter => Message::Stamp,
+            Named::Backspace => Message::UndoStamp,
+            Named::Escape => Message::SwitchView(ViewMode::Play),
+            Named::ArrowUp => Message::SetVolume(10),
+            Named::ArrowDown => Message::SetVolume(-10),
+            _ => Message::Nothing,
+        }
+    } else {
+        Message::Nothing
+    }
+}
+
+fn handle_in_search(key: Key<&str>, modifiers: Modifiers) -> Message {
+    match key {
+        Key::Character("v") if modifiers.command() => Message::SearchPaste,
+        Key::Character(c) => Message::SearchInput(c.to_string()),
+        Key::Named(Named::Space) => Message::SearchInput(" ".to_string()),
+        Key::Named(Named::Backspace) => Message::SearchErase,
+        Key::Named(Named::ArrowUp) => Message::SearchSelect(-1),
+        Key::Named(Named::ArrowDown) => Message::SearchSelect(1),
+        Key::Named(Named::Enter) => Message::SearchJump,
+        Key::Named(Named::Escape) => Message::SwitchView(ViewMode::Play),
+        _ => Message::Nothing,
+    }
+}
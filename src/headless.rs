@@ -0,0 +1,171 @@
+use std::{
+    io::{self, BufRead},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::{cli::Args, i18n::tr, lyrics, player::Player, search::Search, Message};
+
+/// Plays without creating a window, reading commands line by line from stdin
+/// and printing the status and the current lyric line to stdout.
+pub fn run(args: Args) {
+    let mut player = Player::from(args);
+    let tick = Duration::from_secs_f32(player.tick_secs);
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    print_help();
+    print_status(&player);
+
+    let mut current_pos = player.current_pos;
+    let mut lyric = player.current_lyrics();
+    let mut error = None;
+    let mut lyrics_error = None;
+    loop {
+        match rx.recv_timeout(tick) {
+            Ok(line) => match parse_command(line.trim()) {
+                Some(Command::Message(Message::Quit)) => std::process::exit(0),
+                Some(Command::Message(msg)) => {
+                    player.update(msg);
+                    print_status(&player);
+                }
+                Some(Command::Seek(secs)) => {
+                    player.update(Message::UpdateSlider(secs));
+                    player.update(Message::SeekAudio);
+                    print_status(&player);
+                }
+                Some(Command::Search(query)) => {
+                    let mut search = Search::new(&player.config, &player.options);
+                    search.push(&query);
+                    print_hits(&player, &search);
+                    player.search = Some(search);
+                }
+                Some(Command::Go(n)) => {
+                    if let Some(search) = &mut player.search {
+                        search.selected = n.saturating_sub(1);
+                    }
+                    player.update(Message::SearchJump);
+                    print_status(&player);
+                }
+                Some(Command::Status) => print_status(&player),
+                Some(Command::Help) => print_help(),
+                None => println!("{}: {}", tr().unknown_command, line.trim()),
+            },
+            Err(RecvTimeoutError::Timeout) => {
+                player.update(Message::UpdateTime);
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                player.update(Message::UpdateTime);
+                thread::sleep(tick);
+            }
+        }
+
+        if current_pos != player.current_pos {
+            current_pos = player.current_pos;
+            print_status(&player);
+        }
+
+        if player.error != error {
+            error = player.error.clone();
+            if let Some(error) = &error {
+                eprintln!("{error}");
+            }
+        }
+
+        if player.lyrics.error != lyrics_error {
+            lyrics_error = player.lyrics.error.clone();
+            if let Some(error) = &lyrics_error {
+                eprintln!("{error}");
+            }
+        }
+
+        let new_lyric = player.current_lyrics();
+        if new_lyric != lyric {
+            lyric = new_lyric;
+            for line in lyric.iter().filter(|line| !line.is_empty()) {
+                println!("  {}", lyrics::ruby::inline(line, player.show_readings));
+            }
+        }
+    }
+}
+
+enum Command {
+    Message(Message),
+    Seek(f32),
+    Search(String),
+    /// Plays the song of a line found by the last search, counted from 1.
+    Go(usize),
+    Status,
+    Help,
+}
+
+fn parse_command(line: &str) -> Option<Command> {
+    let mut words = line.split_whitespace();
+    let cmd = match words.next()? {
+        "p" | "pause" | "play" => Command::Message(Message::TogglePlay),
+        "n" | "next" => Command::Message(Message::NextSong),
+        "+" => Command::Message(Message::SetVolume(10)),
+        "-" => Command::Message(Message::SetVolume(-10)),
+        "s" | "speed" => Command::Message(Message::ToggleSpeed),
+        "t" | "lang" => Command::Message(Message::ToggleLang),
+        "[" => Command::Message(Message::ShiftLyrics(-100)),
+        "]" => Command::Message(Message::ShiftLyrics(100)),
+        "w" | "save" => Command::Message(Message::SaveLyricOffset),
+        "r" | "ruby" => Command::Message(Message::ToggleReadings),
+        "seek" => Command::Seek(words.next()?.parse().ok()?),
+        "/" | "search" => {
+            let query = words.collect::<Vec<_>>().join(" ");
+            if query.is_empty() {
+                return None;
+            }
+            Command::Search(query)
+        }
+        "go" => Command::Go(words.next()?.parse().ok()?),
+        "i" | "status" => Command::Status,
+        "h" | "help" => Command::Help,
+        "q" | "quit" => Command::Message(Message::Quit),
+        _ => return None,
+    };
+    Some(cmd)
+}
+
+fn print_status(player: &Player) {
+    let tr = tr();
+    println!(
+        "[{}] {}  {}  {}: {}%  {}: {:.1}  {}",
+        tr.play_state(player.sink.is_paused()),
+        player.config[player.current_pos].name,
+        player.time_text(),
+        tr.volume,
+        (player.sink.volume() * 100.0) as i8,
+        tr.speed,
+        player.sink.speed(),
+        player.offset_text(),
+    );
+}
+
+fn print_hits(player: &Player, search: &Search) {
+    if search.hits.is_empty() {
+        println!("{}", tr().no_results);
+    }
+    for (idx, hit) in search.hits.iter().enumerate() {
+        let name = &player.config[hit.song].name;
+        let time = lyrics::format_time(hit.time);
+        println!("{:>4}. {name}  {time}  {}", idx + 1, hit.line);
+    }
+}
+
+fn print_help() {
+    for (cmd, desc) in tr().command_description {
+        println!("{cmd:<12}{desc}");
+    }
+}
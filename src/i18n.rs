@@ -0,0 +1,131 @@
+mod en_us;
+mod ja_jp;
+mod zh_cn;
+
+use std::{env, fmt::Display, sync::OnceLock};
+
+use serde::Deserialize;
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Locale {
+    #[serde(rename = "zh-CN", alias = "zh")]
+    ZhCn,
+    #[serde(rename = "ja-JP", alias = "ja")]
+    JaJp,
+    #[serde(rename = "en-US", alias = "en")]
+    EnUs,
+}
+
+impl Locale {
+    /// Guesses the locale from the usual environment variables, falling back to Chinese.
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_tag(&value))
+            .unwrap_or(Locale::ZhCn)
+    }
+
+    /// Parses tags such as `ja_JP.UTF-8` or `en-US`, only looking at the language.
+    fn from_tag(tag: &str) -> Option<Self> {
+        let lang = tag.split(['_', '-', '.']).next()?.to_lowercase();
+        match lang.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "ja" => Some(Locale::JaJp),
+            "en" => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+
+    fn strings(self) -> &'static Strings {
+        match self {
+            Locale::ZhCn => &zh_cn::STRINGS,
+            Locale::JaJp => &ja_jp::STRINGS,
+            Locale::EnUs => &en_us::STRINGS,
+        }
+    }
+}
+
+/// The text shown by every front-end, one instance per supported locale.
+pub struct Strings {
+    pub played: &'static str,
+    pub volume: &'static str,
+    pub speed: &'static str,
+    pub lyric_offset: &'static str,
+    pub lyric_editor: &'static str,
+    pub lyric_search: &'static str,
+    pub no_results: &'static str,
+    pub playing: &'static str,
+    pub paused: &'static str,
+    pub songs: &'static str,
+    pub help: &'static str,
+    pub quit: &'static str,
+    pub confirm: &'static str,
+    pub unknown_command: &'static str,
+    /// Key bindings of the iced and terminal front-ends.
+    pub key_description: &'static [(&'static str, &'static str)],
+    /// Commands of the headless mode.
+    pub command_description: &'static [(&'static str, &'static str)],
+
+    pub read_config_failed: &'static str,
+    pub parse_config_failed: &'static str,
+    pub read_dir_failed: &'static str,
+    pub no_songs: &'static str,
+    pub open_audio_failed: &'static str,
+    pub decode_audio_failed: &'static str,
+    pub audio_device_failed: &'static str,
+    pub terminal_failed: &'static str,
+    pub read_lyrics_failed: &'static str,
+    pub load_theme_failed: &'static str,
+    pub load_font_failed: &'static str,
+    pub save_lyrics_failed: &'static str,
+    pub lyrics_text_missing: &'static str,
+    pub update_config_failed: &'static str,
+    pub export_failed: &'static str,
+    pub saved_to: &'static str,
+    pub offset_unsupported: &'static str,
+
+    /// Results of `check-config`.
+    pub invalid_lyrics: &'static str,
+    pub read_image_failed: &'static str,
+    pub duplicate_name: &'static str,
+    pub end_before_start: &'static str,
+    pub invalid_speed: &'static str,
+    pub invalid_gain: &'static str,
+    pub config_ok: &'static str,
+    pub problems_found: &'static str,
+}
+
+impl Strings {
+    pub fn play_state(&self, paused: bool) -> &'static str {
+        if paused {
+            self.paused
+        } else {
+            self.playing
+        }
+    }
+}
+
+/// Sets the locale once the config is loaded, detecting it when the config has none.
+pub fn init(locale: Option<Locale>) {
+    let _ = LOCALE.set(locale.unwrap_or_else(Locale::detect));
+}
+
+/// The strings of the current locale.
+pub fn tr() -> &'static Strings {
+    // errors may be reported before the config, and so the locale, is loaded
+    LOCALE
+        .get()
+        .copied()
+        .unwrap_or_else(Locale::detect)
+        .strings()
+}
+
+/// Reports an error in the current locale and exits.
+pub fn fail(msg: &str, err: impl Display) -> ! {
+    eprintln!("{msg}: {err}");
+    std::process::exit(1);
+}
@@ -0,0 +1,109 @@
+use super::Strings;
+
+pub const STRINGS: Strings = Strings {
+    played: "Played",
+    volume: "Volume",
+    speed: "Speed",
+    lyric_offset: "Lyric offset",
+    lyric_editor: "Lyric editor",
+    lyric_search: "Search lyrics",
+    no_results: "No lines found",
+    playing: "Playing",
+    paused: "Paused",
+    songs: "Songs",
+    help: "Help",
+    quit: "Quit?",
+    confirm: "Y / N",
+    unknown_command: "Unknown command",
+    key_description: &[
+        ("\n● Mode/Play", "\n"),
+        ("h", "Open the help page"),
+        ("[p, space]", "Play/pause"),
+        (
+            "t",
+            "Switch language (both by default, then romanized if enabled/Chinese/Japanese/both)",
+        ),
+        ("s", "Switch playback speed"),
+        ("m", "Toggle mini mode"),
+        ("f", "Enter fullscreen lyrics mode"),
+        ("o", "Show/hide desktop lyrics"),
+        ("l", "Lock/unlock desktop lyrics"),
+        ("c", "Switch theme"),
+        ("[ / ]", "Show the lyrics 100ms later/sooner"),
+        ("w", "Save the lyric offset to the lyrics files"),
+        ("r", "Show/hide the readings of the lyrics"),
+        ("e", "Open the lyric editor"),
+        ("/", "Search the lyrics of every song"),
+        ("q", "Quit"),
+        ("\n\n● Mode/Mini", "\n"),
+        ("m", "Leave mini mode"),
+        ("\n\n● Mode/Fullscreen", "\n"),
+        ("[f, esc]", "Leave fullscreen lyrics mode"),
+        ("\n\n● Mode/Help", "\n"),
+        ("h", "Close the help page"),
+        ("\n\n● Mode/Lyric editor", "\n"),
+        (
+            "[space, enter]",
+            "Stamp the current time onto the next line",
+        ),
+        (
+            "backspace",
+            "Undo the last line, replaying from the one before",
+        ),
+        ("r", "Replay from the last line"),
+        ("[ / ]", "Move the last line 100ms earlier/later"),
+        ("t", "Switch between the Chinese and Japanese lyrics"),
+        ("w", "Save as an LRC file"),
+        ("[e, esc]", "Close the lyric editor"),
+        ("\n\n● Mode/Search", "\n"),
+        ("[↑ / ↓]", "Select a line"),
+        ("enter", "Play the song from the selected line"),
+        ("ctrl + v", "Paste into the search"),
+        ("esc", "Close the search"),
+        ("\n\n● Mode/Quit", "\n"),
+        ("y", "Confirm"),
+        ("n", "Cancel"),
+    ],
+    command_description: &[
+        ("p", "Play/pause"),
+        ("n", "Next song"),
+        ("+ / -", "Adjust volume"),
+        ("s", "Switch playback speed"),
+        ("t", "Switch language"),
+        ("[ / ]", "Show the lyrics 100ms later/sooner"),
+        ("w", "Save the lyric offset to the lyrics files"),
+        ("r", "Show/hide the readings of the lyrics"),
+        ("seek <secs>", "Jump to a position"),
+        ("/ <text>", "Search the lyrics of every song"),
+        ("go <n>", "Play the song from the nth line found"),
+        ("i", "Show status"),
+        ("h", "Show help"),
+        ("q", "Quit"),
+    ],
+
+    read_config_failed: "Cannot read the config file",
+    parse_config_failed: "Invalid config file",
+    read_dir_failed: "Cannot read the directory",
+    no_songs: "No songs to play",
+    open_audio_failed: "Cannot open the audio file",
+    decode_audio_failed: "Cannot decode the audio file",
+    audio_device_failed: "Cannot open the audio device",
+    terminal_failed: "Cannot use the terminal",
+    read_lyrics_failed: "Cannot read the lyrics file",
+    load_theme_failed: "Cannot load the theme",
+    load_font_failed: "Cannot load the font",
+    save_lyrics_failed: "Cannot save the lyrics file",
+    lyrics_text_missing: "Cannot find the lyrics text",
+    update_config_failed: "Cannot update the config file",
+    export_failed: "Cannot export the current lyrics to",
+    saved_to: "Saved to",
+    offset_unsupported: "Only LRC files can save an offset",
+    invalid_lyrics: "Invalid lyrics file",
+    read_image_failed: "Cannot read the image",
+    duplicate_name: "Duplicate song name",
+    end_before_start: "The end is not after the start",
+    invalid_speed: "The speed must be above 0",
+    invalid_gain: "The gain must be a finite number",
+    config_ok: "No problems found",
+    problems_found: "Problems found",
+};
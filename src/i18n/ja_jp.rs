@@ -0,0 +1,103 @@
+use super::Strings;
+
+pub const STRINGS: Strings = Strings {
+    played: "再生済み",
+    volume: "音量",
+    speed: "速度",
+    lyric_offset: "歌詞オフセット",
+    lyric_editor: "歌詞エディター",
+    lyric_search: "歌詞を検索",
+    no_results: "見つかりませんでした",
+    playing: "再生中",
+    paused: "一時停止中",
+    songs: "曲",
+    help: "ヘルプ",
+    quit: "終了しますか?",
+    confirm: "Y / N",
+    unknown_command: "不明なコマンド",
+    key_description: &[
+        ("\n● モード/再生", "\n"),
+        ("h", "ヘルプページを開く"),
+        ("[p, space]", "再生/一時停止"),
+        (
+            "t",
+            "言語を切り替え(既定は二言語, 有効ならローマ字/中国語/日本語/二言語の順)",
+        ),
+        ("s", "再生速度を切り替え"),
+        ("m", "ミニモードを切り替え"),
+        ("f", "全画面歌詞モードに入る"),
+        ("o", "デスクトップ歌詞の表示/非表示"),
+        ("l", "デスクトップ歌詞のロック/解除"),
+        ("c", "テーマを切り替え"),
+        ("[ / ]", "歌詞を 100ms 遅らせる/早める"),
+        ("w", "歌詞オフセットを歌詞ファイルに保存"),
+        ("r", "歌詞のふりがなを表示/非表示"),
+        ("e", "歌詞エディターを開く"),
+        ("/", "全曲の歌詞を検索"),
+        ("q", "アプリを終了"),
+        ("\n\n● モード/ミニ", "\n"),
+        ("m", "ミニモードを終了"),
+        ("\n\n● モード/全画面", "\n"),
+        ("[f, esc]", "全画面歌詞モードを終了"),
+        ("\n\n● モード/ヘルプ", "\n"),
+        ("h", "ヘルプページを閉じる"),
+        ("\n\n● モード/歌詞エディター", "\n"),
+        ("[space, enter]", "次の行に現在の時間を付ける"),
+        ("backspace", "前の行を取り消し、その前の行から再生"),
+        ("r", "前の行から再生し直す"),
+        ("[ / ]", "前の行の時間を 100ms 早める/遅らせる"),
+        ("t", "中国語/日本語の歌詞を切り替え"),
+        ("w", "LRC ファイルとして保存"),
+        ("[e, esc]", "歌詞エディターを閉じる"),
+        ("\n\n● モード/検索", "\n"),
+        ("[↑ / ↓]", "行を選択"),
+        ("enter", "選択した行から曲を再生"),
+        ("ctrl + v", "検索欄に貼り付け"),
+        ("esc", "検索を閉じる"),
+        ("\n\n● モード/終了", "\n"),
+        ("y", "確定"),
+        ("n", "キャンセル"),
+    ],
+    command_description: &[
+        ("p", "再生/一時停止"),
+        ("n", "次の曲"),
+        ("+ / -", "音量を調整"),
+        ("s", "再生速度を切り替え"),
+        ("t", "言語を切り替え"),
+        ("[ / ]", "歌詞を 100ms 遅らせる/早める"),
+        ("w", "歌詞オフセットを歌詞ファイルに保存"),
+        ("r", "歌詞のふりがなを表示/非表示"),
+        ("seek <秒>", "指定位置へ移動"),
+        ("/ <テキスト>", "全曲の歌詞を検索"),
+        ("go <n>", "見つかった n 番目の行から曲を再生"),
+        ("i", "状態を表示"),
+        ("h", "ヘルプを表示"),
+        ("q", "アプリを終了"),
+    ],
+
+    read_config_failed: "設定ファイルを読み込めません",
+    parse_config_failed: "設定ファイルの形式が正しくありません",
+    read_dir_failed: "ディレクトリを読み込めません",
+    no_songs: "再生する曲がありません",
+    open_audio_failed: "音声ファイルを開けません",
+    decode_audio_failed: "音声ファイルをデコードできません",
+    audio_device_failed: "オーディオデバイスを開けません",
+    terminal_failed: "端末を使用できません",
+    read_lyrics_failed: "歌詞ファイルを読み込めません",
+    load_theme_failed: "テーマを読み込めません",
+    load_font_failed: "フォントを読み込めません",
+    save_lyrics_failed: "歌詞ファイルを保存できません",
+    lyrics_text_missing: "歌詞のテキストが見つかりません",
+    update_config_failed: "設定ファイルを更新できません",
+    export_failed: "現在の歌詞を書き出せません:",
+    saved_to: "保存しました",
+    offset_unsupported: "オフセットを保存できるのは LRC ファイルだけです",
+    invalid_lyrics: "歌詞ファイルの形式が正しくありません",
+    read_image_failed: "画像を読み込めません",
+    duplicate_name: "曲名が重複しています",
+    end_before_start: "終了位置が開始位置より後ではありません",
+    invalid_speed: "速度は 0 より大きくする必要があります",
+    invalid_gain: "ゲインは有限の数値にする必要があります",
+    config_ok: "問題は見つかりませんでした",
+    problems_found: "見つかった問題",
+};
@@ -0,0 +1,103 @@
+use super::Strings;
+
+pub const STRINGS: Strings = Strings {
+    played: "已播放",
+    volume: "音量",
+    speed: "速度",
+    lyric_offset: "歌词偏移",
+    lyric_editor: "歌词编辑",
+    lyric_search: "搜索歌词",
+    no_results: "没有找到歌词",
+    playing: "播放中",
+    paused: "暂停中",
+    songs: "歌曲",
+    help: "帮助",
+    quit: "退出?",
+    confirm: "Y / N",
+    unknown_command: "未知命令",
+    key_description: &[
+        ("\n● 模式/播放", "\n"),
+        ("h", "进入帮助页面"),
+        ("[p, space]", "播放/暂停"),
+        (
+            "t",
+            "切换语言(默认双语字幕, 每次切换至罗马字(若已开启)/中文/日语/双语)",
+        ),
+        ("s", "切换播放速度"),
+        ("m", "切换迷你模式"),
+        ("f", "进入全屏歌词模式"),
+        ("o", "显示/隐藏桌面歌词"),
+        ("l", "锁定/解锁桌面歌词"),
+        ("c", "切换主题"),
+        ("[ / ]", "歌词延后/提前 100ms"),
+        ("w", "将歌词偏移保存到歌词文件"),
+        ("r", "显示/隐藏歌词注音"),
+        ("e", "进入歌词编辑模式"),
+        ("/", "搜索所有歌曲的歌词"),
+        ("q", "关闭应用"),
+        ("\n\n● 模式/迷你", "\n"),
+        ("m", "退出迷你模式"),
+        ("\n\n● 模式/全屏", "\n"),
+        ("[f, esc]", "退出全屏歌词模式"),
+        ("\n\n● 模式/帮助", "\n"),
+        ("h", "退出帮助页面"),
+        ("\n\n● 模式/歌词编辑", "\n"),
+        ("[space, enter]", "为下一行打上当前时间"),
+        ("backspace", "撤销上一行, 从再上一行重新播放"),
+        ("r", "从上一行重新播放"),
+        ("[ / ]", "上一行的时间提前/延后 100ms"),
+        ("t", "切换中文/日语歌词"),
+        ("w", "保存为 LRC 文件"),
+        ("[e, esc]", "退出歌词编辑模式"),
+        ("\n\n● 模式/搜索", "\n"),
+        ("[↑ / ↓]", "选择一行"),
+        ("enter", "从选中的一行播放歌曲"),
+        ("ctrl + v", "粘贴到搜索框"),
+        ("esc", "退出搜索"),
+        ("\n\n● 模式/退出", "\n"),
+        ("y", "确认"),
+        ("n", "取消"),
+    ],
+    command_description: &[
+        ("p", "播放/暂停"),
+        ("n", "下一首"),
+        ("+ / -", "调整音量"),
+        ("s", "切换播放速度"),
+        ("t", "切换语言"),
+        ("[ / ]", "歌词延后/提前 100ms"),
+        ("w", "将歌词偏移保存到歌词文件"),
+        ("r", "显示/隐藏歌词注音"),
+        ("seek <秒>", "跳转到指定位置"),
+        ("/ <文字>", "搜索所有歌曲的歌词"),
+        ("go <n>", "从找到的第 n 行播放歌曲"),
+        ("i", "显示状态"),
+        ("h", "显示帮助"),
+        ("q", "关闭应用"),
+    ],
+
+    read_config_failed: "无法读取配置文件",
+    parse_config_failed: "配置文件格式错误",
+    read_dir_failed: "无法读取目录",
+    no_songs: "没有可播放的歌曲",
+    open_audio_failed: "无法打开音频文件",
+    decode_audio_failed: "无法解码音频文件",
+    audio_device_failed: "无法打开音频设备",
+    terminal_failed: "无法使用终端",
+    read_lyrics_failed: "无法读取歌词文件",
+    load_theme_failed: "无法加载主题",
+    load_font_failed: "无法加载字体",
+    save_lyrics_failed: "无法保存歌词文件",
+    lyrics_text_missing: "找不到歌词文本",
+    update_config_failed: "无法更新配置文件",
+    export_failed: "无法导出当前歌词到",
+    saved_to: "已保存到",
+    offset_unsupported: "只有 LRC 歌词文件可以保存偏移",
+    invalid_lyrics: "歌词文件格式错误",
+    read_image_failed: "无法读取图片",
+    duplicate_name: "歌曲名重复",
+    end_before_start: "结束位置不在开始位置之后",
+    invalid_speed: "速度必须大于 0",
+    invalid_gain: "增益必须是有限的数值",
+    config_ok: "没有发现问题",
+    problems_found: "发现的问题",
+};
@@ -0,0 +1,644 @@
+mod ass;
+mod normalize;
+mod romanize;
+pub mod ruby;
+mod subtitle;
+mod ttml;
+
+use std::{fs, io, path::Path};
+
+use itertools::Itertools;
+
+pub use romanize::romanize;
+
+use crate::{
+    config::{Config, Normalization, SaveOffset},
+    i18n::tr,
+    player::Player,
+    Lang,
+};
+
+/// Seconds between a line and its translation for them to be shown together.
+const ALIGN_TOLERANCE: f32 = 1.5;
+
+pub type Timeline = Vec<(f32, String)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Lrc,
+    Srt,
+    Vtt,
+    Ttml,
+    Ass,
+}
+
+impl Format {
+    /// Told by the extension of `path`, or else by the content.
+    pub fn detect(path: &str, lyric: &str) -> Self {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("lrc") => Format::Lrc,
+            Some("srt") => Format::Srt,
+            Some("vtt") => Format::Vtt,
+            Some("ttml" | "xml") => Format::Ttml,
+            Some("ass" | "ssa") => Format::Ass,
+            _ => Self::sniff(lyric),
+        }
+    }
+
+    fn sniff(lyric: &str) -> Self {
+        let lyric = lyric.trim_start_matches('\u{feff}').trim_start();
+        if lyric.starts_with("WEBVTT") {
+            Format::Vtt
+        } else if lyric.starts_with("<?xml") || lyric.starts_with("<tt") {
+            Format::Ttml
+        } else if lyric.starts_with("[Script Info]") || lyric.contains("\nDialogue:") {
+            Format::Ass
+        } else if lyric.contains("-->") {
+            Format::Srt
+        } else {
+            Format::Lrc
+        }
+    }
+}
+
+/// The lyrics of the current song, read when it starts playing and again when the
+/// config or their files change, rather than on every update.
+pub struct Lyrics {
+    chinese: Option<Timeline>,
+    japanese: Option<Timeline>,
+    /// The line of the translation shown with each line of the original.
+    pairs: Vec<Option<usize>>,
+    /// Why a file could not be read, its track being left without lyrics meanwhile.
+    pub error: Option<String>,
+}
+
+impl Lyrics {
+    pub fn load(song: &Config, normalization: &Normalization) -> Self {
+        let mut error = None;
+        let mut track = |lang| {
+            let (path, _) = song.lyrics_file(lang)?;
+            match song_track(song, lang, normalization) {
+                Ok(lines) => Some(lines),
+                Err(err) => {
+                    error = Some(format!("{} {path}: {err}", tr().read_lyrics_failed));
+                    None
+                }
+            }
+        };
+
+        let (chinese, japanese) = (track(Lang::Chinese), track(Lang::Japanese));
+        let mut lyrics = Self {
+            chinese,
+            japanese,
+            pairs: vec![],
+            error,
+        };
+        let original = song.original();
+        if let (Some(lines), Some(translated)) =
+            (lyrics.track(original), lyrics.track(translation(original)))
+        {
+            lyrics.pairs = align(lines, translated);
+        }
+        lyrics
+    }
+
+    fn track(&self, lang: Lang) -> Option<&Timeline> {
+        match lang {
+            Lang::Chinese => self.chinese.as_ref(),
+            Lang::Japanese => self.japanese.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// How many lines of each track have started, which changes whenever the current
+/// lines do.
+pub fn started_lines(player: &Player) -> [usize; 2] {
+    let now = lyric_time(player);
+    let lyrics = &player.lyrics;
+    [&lyrics.chinese, &lyrics.japanese].map(|track| {
+        track
+            .as_ref()
+            .map_or(0, |lines| lines.partition_point(|(time, _)| now >= *time))
+    })
+}
+
+pub fn get_lyrics(player: &Player, lang: Lang) -> Option<String> {
+    let now = lyric_time(player);
+    player
+        .lyrics
+        .track(lang)?
+        .iter()
+        .rev()
+        .find(|(time, _)| now >= *time)
+        .map(|(_, s)| s.clone())
+}
+
+/// The current Chinese and Japanese lines. The line of the translation is the one
+/// paired with the current line of the original, so both switch together even when
+/// the timestamps of the files differ slightly.
+pub fn get_bilingual_lyrics(player: &Player) -> (Option<String>, Option<String>) {
+    let original = player.config[player.current_pos].original();
+    let lyrics = &player.lyrics;
+    let (Some(lines), Some(translated)) =
+        (lyrics.track(original), lyrics.track(translation(original)))
+    else {
+        return (
+            get_lyrics(player, Lang::Chinese),
+            get_lyrics(player, Lang::Japanese),
+        );
+    };
+
+    let now = lyric_time(player);
+    let Some(idx) = lines
+        .partition_point(|(time, _)| now >= *time)
+        .checked_sub(1)
+    else {
+        return (None, None);
+    };
+    let pair = lyrics.pairs[idx].map_or_else(String::new, |pair| translated[pair].1.clone());
+
+    let line = lines[idx].1.clone();
+    match original {
+        Lang::Chinese => (Some(line), Some(pair)),
+        _ => (Some(pair), Some(line)),
+    }
+}
+
+fn translation(original: Lang) -> Lang {
+    match original {
+        Lang::Chinese => Lang::Japanese,
+        _ => Lang::Chinese,
+    }
+}
+
+/// Pairs each line of the original with the nearest line of the translation.
+fn align(lines: &[(f32, String)], translated: &[(f32, String)]) -> Vec<Option<usize>> {
+    let distance = |a: f32, b: f32| (a - b).abs();
+    // the last of the lines at the time before `at` is the one shown then
+    let nearest = |at: f32| {
+        let next = lines.partition_point(|(time, _)| *time <= at);
+        match (next.checked_sub(1), lines.get(next)) {
+            (Some(prev), Some((time, _))) if distance(*time, at) < distance(lines[prev].0, at) => {
+                Some(next)
+            }
+            (Some(prev), _) => Some(prev),
+            (None, Some(_)) => Some(next),
+            (None, None) => None,
+        }
+    };
+
+    let mut pairs: Vec<Option<usize>> = vec![None; lines.len()];
+    for (idx, (at, _)) in translated.iter().enumerate() {
+        let Some(line) = nearest(*at) else {
+            continue;
+        };
+        let (time, text) = &lines[line];
+        if text.is_empty() || distance(*at, *time) > ALIGN_TOLERANCE {
+            continue;
+        }
+        // the first of the lines nearest to the original wins
+        let nearer = |pair: usize| distance(translated[pair].0, *time) <= distance(*at, *time);
+        if !pairs[line].is_some_and(nearer) {
+            pairs[line] = Some(idx);
+        }
+    }
+    pairs
+}
+
+fn lyric_time(player: &Player) -> f32 {
+    player.time.as_secs_f32() + player.lyric_offset as f32 / 1000.0
+}
+
+/// The timeline of the lyrics of `lang` of `song`, moved by their offsets and
+/// normalized by the rules of the track. It is empty if the song has none.
+pub fn song_track(
+    song: &Config,
+    lang: Lang,
+    normalization: &Normalization,
+) -> io::Result<Timeline> {
+    let Some((path, combined)) = song.lyrics_file(lang) else {
+        return Ok(vec![]);
+    };
+    let lyric = fs::read_to_string(path)?;
+    let offset = song.lyrics(lang).1 + tag_offset(&lyric);
+    let offset = offset as f32 / 1000.0;
+
+    let mut lines = timeline(Format::detect(path, &lyric), &lyric);
+    if combined {
+        let (original, translation) = split_bilingual(lines);
+        lines = if lang == song.original() {
+            original
+        } else {
+            translation
+        };
+    }
+
+    let rules = normalization.track(lang);
+    let lines = lines
+        .into_iter()
+        .map(|(time, s)| (time - offset, normalize::normalize(rules, &s)))
+        .collect();
+    Ok(lines)
+}
+
+/// Splits the original from the translation, given on the next line with the same
+/// time or after ` / `.
+fn split_bilingual(lines: Timeline) -> (Timeline, Timeline) {
+    let slashed = lines.iter().filter(|(_, s)| s.contains(" / ")).count();
+    if slashed > 0 && slashed * 2 >= lines.len() {
+        return lines
+            .into_iter()
+            .map(|(time, s)| match s.split_once(" / ") {
+                Some((original, translation)) => (
+                    (time, original.trim().to_string()),
+                    (time, translation.trim().to_string()),
+                ),
+                None => ((time, s), (time, String::new())),
+            })
+            .unzip();
+    }
+
+    // the timeline keeps the order of the lines with the same time
+    let (mut original, mut translation) = (vec![], vec![]);
+    for (_, mut group) in &lines.into_iter().group_by(|(time, _)| *time) {
+        original.extend(group.next());
+        translation.extend(group.next());
+    }
+    (original, translation)
+}
+
+pub fn timeline(format: Format, lyric: &str) -> Timeline {
+    let mut lines = match format {
+        Format::Lrc => lyric
+            .lines()
+            .filter_map(parse_line)
+            .map(|(time, s)| (time, s.to_string()))
+            .collect_vec(),
+        Format::Srt | Format::Vtt => from_cues(subtitle::parse(lyric)),
+        Format::Ttml => from_cues(ttml::parse(lyric)),
+        Format::Ass => from_cues(ass::parse(lyric)),
+    };
+    lines.sort_by(|a, b| a.0.total_cmp(&b.0));
+    lines
+}
+
+/// Writes `offset`, in milliseconds, into the LRC file at `path`, leaving it as it
+/// is when there is no offset.
+pub fn save_offset(path: &str, offset: i64, mode: SaveOffset) -> io::Result<()> {
+    if offset == 0 {
+        return Ok(());
+    }
+    let lyric = fs::read_to_string(path)?;
+    if Format::detect(path, &lyric) != Format::Lrc {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            tr().offset_unsupported,
+        ));
+    }
+    let lines = match mode {
+        SaveOffset::Tag => {
+            let total = tag_offset(&lyric) + offset;
+            let mut lines = lyric
+                .lines()
+                .filter(|line| parse_offset(line).is_none())
+                .map(str::to_string)
+                .collect_vec();
+            // offsets that cancel out leave no tag
+            if total != 0 {
+                lines.insert(0, format!("[offset:{total:+}]"));
+            }
+            lines
+        }
+        SaveOffset::Retime => lyric
+            .lines()
+            .map(|line| match parse_time(line) {
+                Some((time, s)) => {
+                    let time = (time - offset as f32 / 1000.0).max(0.0);
+                    format!("{}{s}", format_time(time))
+                }
+                None => line.to_string(),
+            })
+            .collect_vec(),
+    };
+    fs::write(path, lines.join("\n") + "\n")
+}
+
+/// Formats `secs` as the `[mm:ss.xx]` timestamp of a lyric line.
+pub fn format_time(secs: f32) -> String {
+    // rounded first, so 59.999 becomes 01:00.00 rather than 00:60.00
+    let centis = (secs * 100.0).round() as u32;
+    format!(
+        "[{:02}:{:02}.{:02}]",
+        centis / 6000,
+        centis / 100 % 60,
+        centis % 100
+    )
+}
+
+/// The text of the lines of the lyrics file at `path` without their timestamps,
+/// leaving out the tags and the empty lines. Plain text files give all their lines.
+pub fn text_lines(path: &str, lyric: &str) -> Vec<String> {
+    let format = Format::detect(path, lyric);
+    if format != Format::Lrc {
+        return timeline(format, lyric)
+            .into_iter()
+            .map(|(_, s)| s)
+            .filter(|line| !line.is_empty())
+            .collect();
+    }
+
+    lyric
+        .lines()
+        .filter_map(|line| match parse_time(line) {
+            Some((_, s)) => Some(s.trim()),
+            None if line.starts_with('[') => None,
+            None => Some(line.trim()),
+        })
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The `[offset:]` tag of `lyric`, in milliseconds.
+fn tag_offset(lyric: &str) -> i64 {
+    lyric.lines().find_map(parse_offset).unwrap_or(0)
+}
+
+fn parse_offset(line: &str) -> Option<i64> {
+    let offset = line.trim().strip_prefix("[offset:")?.strip_suffix(']')?;
+    offset.trim().parse().ok()
+}
+
+/// Checks that the lyrics file at `path` has timed lines, returning the numbers of
+/// the lines of an LRC file whose timestamp is malformed otherwise.
+pub fn validate(path: &str, lyric: &str) -> Result<(), Vec<usize>> {
+    let format = Format::detect(path, lyric);
+    if format != Format::Lrc {
+        let lines = timeline(format, lyric);
+        return if lines.is_empty() {
+            Err(vec![])
+        } else {
+            Ok(())
+        };
+    }
+
+    let bad = lyric
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| is_timed(line) && parse_time(line).is_none())
+        .map(|(idx, _)| idx + 1)
+        .collect_vec();
+
+    if bad.is_empty() && lyric.lines().any(|line| parse_line(line).is_some()) {
+        Ok(())
+    } else {
+        Err(bad)
+    }
+}
+
+/// Splits a `[mm:ss.xx]text` line into its time in seconds and its text.
+fn parse_line(line: &str) -> Option<(f32, &str)> {
+    parse_time(line).filter(|(_, s)| !s.is_empty())
+}
+
+fn parse_time(line: &str) -> Option<(f32, &str)> {
+    if !is_timed(line) {
+        return None;
+    }
+    let (stamp, text) = line[1..].split_once(']')?;
+    let (minute, second) = stamp.split_once(':')?;
+    let time = minute
+        .parse::<f32>()
+        .and_then(|m| second.parse::<f32>().map(|s| m * 60.0 + s))
+        .ok()?;
+    Some((time, text))
+}
+
+/// Whether `line` starts with a timestamp rather than a tag like `[ar:...]`.
+fn is_timed(line: &str) -> bool {
+    line.starts_with('[') && line[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Turns cues into a timeline, with empty lines where no cue is shown.
+fn from_cues(mut cues: Vec<(f32, f32, String)>) -> Timeline {
+    cues.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut lines = vec![];
+    for (idx, (start, end, text)) in cues.iter().enumerate() {
+        lines.push((*start, text.clone()));
+        let next = cues.get(idx + 1).map_or(f32::INFINITY, |cue| cue.0);
+        if *end < next {
+            lines.push((*end, String::new()));
+        }
+    }
+    lines
+}
+
+/// Parses `[hh:]mm:ss.fff` or `[hh:]mm:ss,fff` into seconds.
+fn parse_clock(s: &str) -> Option<f32> {
+    s.trim()
+        .replace(',', ".")
+        .split(':')
+        .try_fold(0.0, |secs, part| {
+            Some(secs * 60.0 + part.parse::<f32>().ok()?)
+        })
+}
+
+fn strip_markup(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start..start + end + 1];
+        if tag[1..].starts_with("br") {
+            result.push(' ');
+        } else if is_ruby_tag(tag) {
+            // kept to show the readings above the text
+            result.push_str(tag);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+
+    let result = result
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    result.split_whitespace().join(" ")
+}
+
+fn is_ruby_tag(tag: &str) -> bool {
+    let name = tag.trim_matches(['<', '>', '/']);
+    ["ruby", "rt", "rp"].contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_time_reads_the_timestamp() {
+        assert_eq!(parse_time("[01:02.50]歌词"), Some((62.5, "歌词")));
+        assert_eq!(parse_time("[00:00.00]"), Some((0.0, "")));
+        assert_eq!(parse_time("[ar:someone]"), None);
+    }
+
+    #[test]
+    fn parse_time_rejects_malformed_timestamps() {
+        assert_eq!(parse_time("[00:歌词]"), None);
+        assert_eq!(parse_time("[00:12"), None);
+        assert_eq!(parse_time("[0012.34]歌词"), None);
+    }
+
+    #[test]
+    fn validate_reports_malformed_lines() {
+        let lyric = "[ti:title]\n[00:01.00]一\n[00:歌词]\n[00:03.00]三";
+        assert_eq!(validate("a.lrc", lyric), Err(vec![3]));
+        assert_eq!(validate("a.lrc", "[00:01.00]一"), Ok(()));
+        assert_eq!(validate("a.lrc", "[ti:title]"), Err(vec![]));
+    }
+
+    #[test]
+    fn from_cues_adds_empty_lines_between_cues() {
+        let cues = vec![
+            (3.0, 4.0, "b".to_string()),
+            (1.0, 2.0, "a".to_string()),
+            (3.5, 5.0, "c".to_string()),
+        ];
+        let lines = vec![
+            (1.0, "a".to_string()),
+            (2.0, String::new()),
+            (3.0, "b".to_string()),
+            (3.5, "c".to_string()),
+            (5.0, String::new()),
+        ];
+        assert_eq!(from_cues(cues), lines);
+    }
+
+    #[test]
+    fn parse_clock_reads_hours_and_commas() {
+        assert_eq!(parse_clock("01:02:03,5"), Some(3723.5));
+        assert_eq!(parse_clock(" 02:03.25 "), Some(123.25));
+        assert_eq!(parse_clock("1:xx"), None);
+    }
+
+    fn timeline(lines: &[(f32, &str)]) -> Timeline {
+        lines.iter().map(|(t, s)| (*t, s.to_string())).collect()
+    }
+
+    #[test]
+    fn split_bilingual_splits_slashed_lines() {
+        let lines = timeline(&[(1.0, "君 / 你"), (2.0, "間奏"), (3.0, "空 /  天空 ")]);
+        let (original, translation) = split_bilingual(lines);
+        assert_eq!(
+            original,
+            timeline(&[(1.0, "君"), (2.0, "間奏"), (3.0, "空")])
+        );
+        assert_eq!(
+            translation,
+            timeline(&[(1.0, "你"), (2.0, ""), (3.0, "天空")])
+        );
+    }
+
+    #[test]
+    fn split_bilingual_pairs_lines_with_the_same_time() {
+        // a single slash does not make the file slashed
+        let lines = timeline(&[
+            (1.0, "君"),
+            (1.0, "你"),
+            (2.0, "A / B"),
+            (3.0, "空"),
+            (3.0, "天空"),
+            (3.0, "多余"),
+        ]);
+        let (original, translation) = split_bilingual(lines);
+        assert_eq!(
+            original,
+            timeline(&[(1.0, "君"), (2.0, "A / B"), (3.0, "空")])
+        );
+        assert_eq!(translation, timeline(&[(1.0, "你"), (3.0, "天空")]));
+    }
+
+    #[test]
+    fn align_pairs_the_nearest_lines_within_the_tolerance() {
+        let lines = timeline(&[(1.0, "a"), (5.0, "b"), (10.0, "c")]);
+        let translated = timeline(&[(1.3, "A"), (4.0, "B"), (12.0, "C")]);
+        assert_eq!(align(&lines, &translated), vec![Some(0), Some(1), None]);
+    }
+
+    #[test]
+    fn align_keeps_the_translation_nearest_to_the_line() {
+        let lines = timeline(&[(1.0, "a"), (2.0, "b")]);
+        // 1.8 is within the tolerance of "a", but belongs to "b"
+        let translated = timeline(&[(1.8, "B"), (2.4, "B'")]);
+        assert_eq!(align(&lines, &translated), vec![None, Some(0)]);
+
+        let lines = timeline(&[(1.0, "a")]);
+        let translated = timeline(&[(0.5, "A"), (1.5, "A'")]);
+        assert_eq!(align(&lines, &translated), vec![Some(0)]);
+    }
+
+    #[test]
+    fn align_leaves_empty_lines_alone() {
+        let lines = timeline(&[(1.0, "a"), (3.0, ""), (6.0, "c")]);
+        let translated = timeline(&[(1.0, "A"), (3.0, "B"), (6.0, "C")]);
+        assert_eq!(align(&lines, &translated), vec![Some(0), None, Some(2)]);
+    }
+
+    #[test]
+    fn format_time_rounds_to_centiseconds() {
+        assert_eq!(format_time(62.5), "[01:02.50]");
+        assert_eq!(format_time(59.999), "[01:00.00]");
+        assert_eq!(format_time(0.004), "[00:00.00]");
+    }
+
+    /// Saves `offset` into an LRC file with `lyric`, returning what it then holds.
+    fn saved(name: &str, lyric: &str, offset: i64, mode: SaveOffset) -> String {
+        let path =
+            std::env::temp_dir().join(format!("deci-player-{}-{name}.lrc", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, lyric).unwrap();
+        save_offset(path, offset, mode).unwrap();
+        let saved = fs::read_to_string(path).unwrap();
+        fs::remove_file(path).unwrap();
+        saved
+    }
+
+    #[test]
+    fn save_offset_adds_to_the_tag() {
+        let lyric = "[ti:title]\n[offset:+200]\n[00:01.00]一";
+        let saved = saved("tag", lyric, -500, SaveOffset::Tag);
+        assert_eq!(saved, "[offset:-300]\n[ti:title]\n[00:01.00]一\n");
+    }
+
+    #[test]
+    fn save_offset_drops_a_tag_that_comes_to_zero() {
+        let lyric = "[offset:+200]\n[00:01.00]一";
+        assert_eq!(
+            saved("cancel", lyric, -200, SaveOffset::Tag),
+            "[00:01.00]一\n"
+        );
+        // no offset leaves the file untouched
+        assert_eq!(saved("zero", lyric, 0, SaveOffset::Tag), lyric);
+    }
+
+    #[test]
+    fn save_offset_shifts_the_timestamps() {
+        let lyric = "[ti:title]\n[00:01.00]一\n[00:00.20]二";
+        let saved = saved("retime", lyric, 500, SaveOffset::Retime);
+        assert_eq!(saved, "[ti:title]\n[00:00.50]一\n[00:00.00]二\n");
+    }
+}
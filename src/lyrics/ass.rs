@@ -0,0 +1,88 @@
+use itertools::Itertools;
+
+use super::parse_clock;
+
+/// The `Dialogue` cues of an ASS or SSA file, with their times in seconds.
+pub fn parse(lyric: &str) -> Vec<(f32, f32, String)> {
+    let mut cues = vec![];
+    let mut in_events = false;
+    let mut format = vec![];
+    for line in lyric.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+        if let Some(fields) = line.strip_prefix("Format:") {
+            format = fields
+                .split(',')
+                .map(|field| field.trim().to_lowercase())
+                .collect();
+            continue;
+        }
+        let Some(values) = line.strip_prefix("Dialogue:") else {
+            continue;
+        };
+
+        // the text is the last field, and may have commas
+        let values = values
+            .splitn(format.len(), ',')
+            .map(str::trim)
+            .collect_vec();
+        let field = |name: &str| {
+            let idx = format.iter().position(|field| field == name)?;
+            values.get(idx).copied()
+        };
+        let start = field("start").and_then(parse_clock);
+        let end = field("end").and_then(parse_clock);
+        if let (Some(start), Some(end), Some(text)) = (start, end, field("text")) {
+            cues.push((start, end, strip_overrides(text)));
+        }
+    }
+    cues
+}
+
+fn strip_overrides(text: &str) -> String {
+    let mut result = String::new();
+    let mut in_block = false;
+    for c in text.chars() {
+        match c {
+            '{' => in_block = true,
+            '}' => in_block = false,
+            c if !in_block => result.push(c),
+            _ => (),
+        }
+    }
+    result
+        .replace("\\N", " ")
+        .replace("\\n", " ")
+        .replace("\\h", " ")
+        .split_whitespace()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_dialogue_lines_in_format_order() {
+        let ass = "[Script Info]\nTitle: test\n\n\
+                   [V4+ Styles]\nFormat: Name, Fontname\nStyle: Default,Arial\n\n\
+                   [Events]\nFormat: Layer, Start, End, Style, Text\n\
+                   Dialogue: 0,0:00:01.50,0:00:03.00,Default,{\\k20}Hello, world\\Nagain\n\
+                   Comment: 0,0:00:04.00,0:00:05.00,Default,Skipped\n";
+        assert_eq!(
+            parse(ass),
+            vec![(1.5, 3.0, "Hello, world again".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_ignores_dialogue_outside_events() {
+        let ass = "[Script Info]\nDialogue: 0,0:00:01.00,0:00:02.00,Default,Skipped\n";
+        assert!(parse(ass).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::config::{Normalize, Script, Width};
+
+const T2S: &str = include_str!("../../dict/t2s.txt");
+
+const CREDITS: &str =
+    "作词 作曲 编曲 作詞 編曲 词 曲 制作人 製作人 监制 混音 录音 和声 演唱 原唱 翻唱 歌 \
+    歌词 歌詞 翻译 翻訳 訳 by lyrics lyricist music composer arranger arrangement vocal vocals \
+    translation translator";
+
+/// `line` after the rules of its track. Credit lines are left empty rather than removed.
+pub fn normalize(rules: &Normalize, line: &str) -> String {
+    if rules.strip_credits && is_credit(line) {
+        return String::new();
+    }
+
+    let mut line = line.to_string();
+    for (from, to) in rules.replace.iter().filter(|(from, _)| !from.is_empty()) {
+        line = line.replace(from, to);
+    }
+    if let Some(width) = rules.width {
+        line = line.chars().map(|c| to_width(c, width)).collect();
+    }
+    if let Some(script) = rules.script {
+        let map = match script {
+            Script::Simplified => &scripts().0,
+            Script::Traditional => &scripts().1,
+        };
+        line = line.chars().map(|c| *map.get(&c).unwrap_or(&c)).collect();
+    }
+    line
+}
+
+pub fn to_width(c: char, width: Width) -> char {
+    match (width, c) {
+        (Width::Half, '！'..='～') => char::from_u32(c as u32 - 0xfee0).unwrap(),
+        (Width::Half, '\u{3000}') => ' ',
+        (Width::Full, '!'..='~') => char::from_u32(c as u32 + 0xfee0).unwrap(),
+        (Width::Full, ' ') => '\u{3000}',
+        _ => c,
+    }
+}
+
+fn is_credit(line: &str) -> bool {
+    let line = line.trim();
+    if line.starts_with('[') && line.ends_with(']') && line.contains(':') {
+        return true;
+    }
+    let Some((key, _)) = line.split_once([':', '：']) else {
+        return false;
+    };
+    let key = key.trim().to_lowercase();
+    CREDITS.split_whitespace().any(|credit| credit == key)
+}
+
+fn scripts() -> &'static (HashMap<char, char>, HashMap<char, char>) {
+    static SCRIPTS: OnceLock<(HashMap<char, char>, HashMap<char, char>)> = OnceLock::new();
+    SCRIPTS.get_or_init(|| {
+        let (mut simplified, mut traditional) = (HashMap::new(), HashMap::new());
+        let pairs = T2S
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .flat_map(str::split_whitespace);
+        for pair in pairs {
+            let mut chars = pair.chars();
+            if let (Some(from), Some(to)) = (chars.next(), chars.next()) {
+                simplified.insert(from, to);
+                traditional.entry(to).or_insert(from);
+            }
+        }
+        (simplified, traditional)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_applies_the_rules_in_order() {
+        let rules = Normalize {
+            strip_credits: true,
+            replace: vec![("A".into(), "B".into()), (String::new(), "x".into())],
+            width: Some(Width::Full),
+            script: Some(Script::Simplified),
+        };
+        assert_eq!(normalize(&rules, "作词：某人"), "");
+        assert_eq!(normalize(&rules, "A國 1"), "Ｂ国\u{3000}１");
+    }
+
+    #[test]
+    fn normalize_to_traditional_takes_the_first_form() {
+        let rules = Normalize {
+            script: Some(Script::Traditional),
+            ..Normalize::default()
+        };
+        // 发 is 髮 in 头发, but only one form can be chosen
+        assert_eq!(normalize(&rules, "头发，国"), "頭發 國");
+    }
+
+    #[test]
+    fn to_width_converts_ascii_and_spaces() {
+        assert_eq!(to_width('Ａ', Width::Half), 'A');
+        assert_eq!(to_width('～', Width::Half), '~');
+        assert_eq!(to_width('\u{3000}', Width::Half), ' ');
+        assert_eq!(to_width('!', Width::Full), '！');
+        assert_eq!(to_width('あ', Width::Full), 'あ');
+        assert_eq!(to_width('A', Width::Half), 'A');
+    }
+
+    #[test]
+    fn is_credit_reads_the_key_before_the_colon() {
+        assert!(is_credit("作曲 : 某人"));
+        assert!(is_credit("Lyrics: someone"));
+        assert!(is_credit(" [by:someone] "));
+        assert!(!is_credit("时间: 不等人"));
+        assert!(!is_credit("作词某人"));
+    }
+}
@@ -0,0 +1,253 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use super::{normalize, ruby};
+use crate::{config::Width, Lang};
+
+const PINYIN: &str = include_str!("../../dict/pinyin.txt");
+
+/// `line` in Hepburn romaji or pinyin, keeping the characters it cannot read.
+pub fn romanize(line: &str, lang: Lang) -> String {
+    let text = ruby::segments(line)
+        .into_iter()
+        .map(|(base, reading)| reading.unwrap_or(base))
+        .collect::<String>();
+    let romanized = match lang {
+        Lang::Chinese => pinyin(&text),
+        _ => romaji(&text),
+    };
+    romanized.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn pinyin(text: &str) -> String {
+    let dict = dict();
+    let mut result = String::new();
+    let mut after_syllable = false;
+    for c in text.chars() {
+        if let Some(syllable) = dict.get(&c) {
+            result.push(' ');
+            result.push_str(syllable);
+            after_syllable = true;
+        } else {
+            if after_syllable && c.is_alphanumeric() {
+                result.push(' ');
+            }
+            result.push_str(&punctuation(c));
+            after_syllable = false;
+        }
+    }
+    result
+}
+
+fn dict() -> &'static HashMap<char, &'static str> {
+    static DICT: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
+    DICT.get_or_init(|| {
+        let mut dict = HashMap::new();
+        for line in PINYIN.lines().filter(|line| !line.starts_with('#')) {
+            let Some((syllable, chars)) = line.split_once(' ') else {
+                continue;
+            };
+            for c in chars.chars() {
+                dict.entry(c).or_insert(syllable);
+            }
+        }
+        dict
+    })
+}
+
+fn romaji(text: &str) -> String {
+    let mut result = String::new();
+    // a small tsu doubles the consonant after it
+    let mut double = false;
+    for c in text.chars().map(to_hiragana) {
+        match c {
+            'っ' => double = true,
+            'ー' => {
+                if let Some(vowel) = result.chars().last().filter(|c| "aiueo".contains(*c)) {
+                    result.push(vowel);
+                }
+            }
+            'ゃ' | 'ゅ' | 'ょ' => {
+                let vowel = small_y(c);
+                if result.ends_with("shi") || result.ends_with("chi") || result.ends_with("ji") {
+                    result.pop();
+                    result.push_str(&vowel[1..]);
+                } else if result.ends_with('i') {
+                    result.pop();
+                    result.push_str(vowel);
+                } else {
+                    result.push_str(vowel);
+                }
+            }
+            'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' => {
+                let vowel = kana(c).unwrap();
+                // ふぁ is fa, ウィ is wi
+                let mut last = result.chars().rev();
+                let (last, before) = (last.next(), last.next());
+                let is_consonant = |c: char| c.is_ascii_lowercase() && !"aiueo".contains(c);
+                if last.is_some_and(|c| "aiueo".contains(c)) && before.is_some_and(is_consonant) {
+                    result.pop();
+                } else if last == Some('u') {
+                    result.pop();
+                    result.push('w');
+                }
+                result.push_str(vowel);
+            }
+            c => match kana(c) {
+                Some(romaji) => {
+                    if double {
+                        let consonant = if romaji.starts_with("ch") {
+                            "t"
+                        } else {
+                            &romaji[..1]
+                        };
+                        if !"aiueon".contains(consonant) {
+                            result.push_str(consonant);
+                        }
+                    }
+                    result.push_str(romaji);
+                }
+                None => result.push_str(&punctuation(c)),
+            },
+        }
+        if c != 'っ' {
+            double = false;
+        }
+    }
+    result
+}
+
+fn to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap(),
+        c => c,
+    }
+}
+
+fn small_y(c: char) -> &'static str {
+    match c {
+        'ゃ' => "ya",
+        'ゅ' => "yu",
+        _ => "yo",
+    }
+}
+
+fn kana(c: char) -> Option<&'static str> {
+    let romaji = match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' => "e",
+        'お' | 'ぉ' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' => "ya",
+        'ゆ' => "yu",
+        'よ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ゐ' => "i",
+        'ゑ' => "e",
+        'を' => "o",
+        'ん' => "n",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ゔ' => "vu",
+        _ => return None,
+    };
+    Some(romaji)
+}
+
+fn punctuation(c: char) -> String {
+    match c {
+        '、' | '，' => ", ".to_string(),
+        '。' => ". ".to_string(),
+        '・' | '　' => " ".to_string(),
+        '「' | '」' | '『' | '』' => "\"".to_string(),
+        '〜' => "~".to_string(),
+        c => normalize::to_width(c, Width::Half).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn romaji_joins_small_kana() {
+        assert_eq!(romaji("きょう"), "kyou");
+        assert_eq!(romaji("しゃしん"), "shashin");
+        assert_eq!(romaji("ジュース"), "juusu");
+        assert_eq!(romaji("ファン"), "fan");
+        assert_eq!(romaji("ウィ"), "wi");
+    }
+
+    #[test]
+    fn romaji_doubles_the_consonant_after_a_small_tsu() {
+        assert_eq!(romaji("がっこう"), "gakkou");
+        assert_eq!(romaji("ちょっと"), "chotto");
+        assert_eq!(romaji("マッチ"), "matchi");
+        // nothing to double before a vowel or the end
+        assert_eq!(romaji("あっ"), "a");
+    }
+
+    #[test]
+    fn romanize_reads_the_annotations() {
+        assert_eq!(
+            romanize("空{そら}、ラーメン", Lang::Japanese),
+            "sora, raamen"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+/// The base texts of `line` with their readings, written as `漢字{かんじ}`,
+/// `｜base{reading}` or `<ruby>漢字<rt>かんじ</rt></ruby>`.
+pub fn segments(line: &str) -> Vec<(String, Option<String>)> {
+    let mut segments = vec![];
+    let mut plain = String::new();
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("<ruby>") {
+            let end = after.find("</ruby>").unwrap_or(after.len());
+            flush(&mut plain, &mut segments);
+            segments.extend(html_ruby(&after[..end]));
+            rest = after.get(end + "</ruby>".len()..).unwrap_or("");
+            continue;
+        }
+        if c == '｜' {
+            if let Some((base, after)) = rest[c.len_utf8()..].split_once('{') {
+                if let Some((reading, after)) = after.split_once('}') {
+                    flush(&mut plain, &mut segments);
+                    segments.push((base.to_string(), Some(reading.to_string())));
+                    rest = after;
+                    continue;
+                }
+            }
+        }
+        if c == '{' {
+            let start = plain
+                .char_indices()
+                .rev()
+                .take_while(|(_, c)| is_kanji(*c))
+                .last()
+                .map(|(idx, _)| idx);
+            if let (Some(start), Some((reading, after))) = (start, rest[1..].split_once('}')) {
+                let base = plain.split_off(start);
+                flush(&mut plain, &mut segments);
+                segments.push((base, Some(reading.to_string())));
+                rest = after;
+                continue;
+            }
+        }
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    flush(&mut plain, &mut segments);
+    segments
+}
+
+pub fn has_readings(line: &str) -> bool {
+    segments(line).iter().any(|(_, reading)| reading.is_some())
+}
+
+pub fn plain(line: &str) -> String {
+    segments(line).into_iter().map(|(base, _)| base).collect()
+}
+
+/// `line` for plain text output, with the readings in parentheses.
+pub fn inline(line: &str, show_readings: bool) -> String {
+    segments(line)
+        .into_iter()
+        .map(|(base, reading)| match reading {
+            Some(reading) if show_readings => format!("{base}({reading})"),
+            _ => base,
+        })
+        .collect()
+}
+
+fn html_ruby(content: &str) -> Vec<(String, Option<String>)> {
+    let mut content = content.to_string();
+    while let Some(start) = content.find("<rp>") {
+        let end = content[start..]
+            .find("</rp>")
+            .map_or(content.len(), |end| start + end + "</rp>".len());
+        content.replace_range(start..end, "");
+    }
+
+    let mut segments = vec![];
+    let mut rest = content.as_str();
+    while let Some((base, after)) = rest.split_once("<rt>") {
+        let (reading, after) = after.split_once("</rt>").unwrap_or((after, ""));
+        segments.push((base.to_string(), Some(reading.to_string())));
+        rest = after;
+    }
+    if !rest.is_empty() {
+        segments.push((rest.to_string(), None));
+    }
+    segments
+}
+
+fn flush(plain: &mut String, segments: &mut Vec<(String, Option<String>)>) {
+    if !plain.is_empty() {
+        segments.push((std::mem::take(plain), None));
+    }
+}
+
+fn is_kanji(c: char) -> bool {
+    matches!(c,
+        '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' | '\u{f900}'..='\u{faff}'
+        | '々' | '〆' | 'ヶ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ruby(base: &str, reading: Option<&str>) -> (String, Option<String>) {
+        (base.to_string(), reading.map(str::to_string))
+    }
+
+    #[test]
+    fn segments_read_the_run_of_kanji_before_braces() {
+        assert_eq!(
+            segments("青い空{そら}を見た"),
+            vec![
+                ruby("青い", None),
+                ruby("空", Some("そら")),
+                ruby("を見た", None),
+            ]
+        );
+        assert_eq!(
+            segments("時々{ときどき}"),
+            vec![ruby("時々", Some("ときどき"))]
+        );
+        // braces after other text are kept as they are
+        assert_eq!(segments("かな{かな}"), vec![ruby("かな{かな}", None)]);
+    }
+
+    #[test]
+    fn segments_read_the_base_after_a_bar() {
+        assert_eq!(
+            segments("君は｜明日{あした}へ"),
+            vec![
+                ruby("君は", None),
+                ruby("明日", Some("あした")),
+                ruby("へ", None),
+            ]
+        );
+        assert_eq!(segments("｜未完{"), vec![ruby("｜未完{", None)]);
+    }
+
+    #[test]
+    fn segments_read_html_ruby() {
+        assert_eq!(
+            segments("<ruby>漢<rp>(</rp><rt>かん</rt><rp>)</rp>字<rt>じ</rt></ruby>だ"),
+            vec![
+                ruby("漢", Some("かん")),
+                ruby("字", Some("じ")),
+                ruby("だ", None),
+            ]
+        );
+        assert_eq!(plain("<ruby>夢<rt>ゆめ</rt></ruby>"), "夢");
+        assert_eq!(inline("夢{ゆめ}", true), "夢(ゆめ)");
+        assert!(!has_readings("夢"));
+    }
+}
@@ -0,0 +1,52 @@
+use itertools::Itertools;
+
+use super::{parse_clock, strip_markup};
+
+/// The cues of an SRT or WebVTT file, with their times in seconds.
+pub fn parse(lyric: &str) -> Vec<(f32, f32, String)> {
+    let mut cues = vec![];
+    let mut lines = lyric.lines();
+    while let Some(line) = lines.next() {
+        let Some((start, end)) = line.split_once("-->") else {
+            continue;
+        };
+        // the settings of a WebVTT cue follow its end
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let (Some(start), Some(end)) = (parse_clock(start), parse_clock(end)) else {
+            continue;
+        };
+
+        let text = lines
+            .by_ref()
+            .take_while(|line| !line.trim().is_empty())
+            .map(strip_markup)
+            .join(" ");
+        cues.push((start, end, text));
+    }
+    cues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_srt_cues() {
+        let srt = "1\n00:00:01,500 --> 00:00:03,000\n<i>Hello</i>\nworld\n\n\
+                   2\n00:00:04,000 --> 00:00:05,000\nSecond\n";
+        assert_eq!(
+            parse(srt),
+            vec![
+                (1.5, 3.0, "Hello world".to_string()),
+                (4.0, 5.0, "Second".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_reads_vtt_cues_with_settings() {
+        let vtt = "WEBVTT\n\n00:01.000 --> 00:02.000 align:start line:0\nOne\n\n\
+                   bad --> 00:03.000\nSkipped\n";
+        assert_eq!(parse(vtt), vec![(1.0, 2.0, "One".to_string())]);
+    }
+}
@@ -0,0 +1,90 @@
+use super::{parse_clock, strip_markup};
+
+/// The `<p>` cues of a TTML file, with their times in seconds.
+pub fn parse(lyric: &str) -> Vec<(f32, f32, String)> {
+    let mut cues = vec![];
+    let mut rest = lyric;
+    while let Some((start, name)) = find_p(rest) {
+        rest = &rest[start..];
+        let Some(open_end) = rest.find('>') else {
+            break;
+        };
+        let close = format!("</{name}>");
+        let Some(close_start) = rest.find(&close) else {
+            break;
+        };
+        let (open, body) = (&rest[..open_end], &rest[open_end + 1..close_start]);
+        rest = &rest[close_start + close.len()..];
+
+        let Some(begin) = attr(open, "begin").and_then(parse_time) else {
+            continue;
+        };
+        let end = attr(open, "end")
+            .and_then(parse_time)
+            .or_else(|| Some(begin + attr(open, "dur").and_then(parse_time)?));
+        if let Some(end) = end {
+            cues.push((begin, end, strip_markup(body)));
+        }
+    }
+    cues
+}
+
+fn find_p(lyric: &str) -> Option<(usize, &str)> {
+    lyric.match_indices('<').find_map(|(idx, _)| {
+        let name = lyric[idx + 1..]
+            .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .next()?;
+        (name == "p" || name.ends_with(":p")).then_some((idx, name))
+    })
+}
+
+fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {name}="))? + name.len() + 2;
+    let quote = tag[start..].chars().next()?;
+    let value = &tag[start + 1..];
+    value.get(..value.find(quote)?)
+}
+
+fn parse_time(time: &str) -> Option<f32> {
+    let time = time.trim();
+    let offset =
+        |suffix: &str, scale: f32| Some(time.strip_suffix(suffix)?.parse::<f32>().ok()? * scale);
+
+    offset("ms", 0.001)
+        .or_else(|| offset("s", 1.0))
+        .or_else(|| offset("m", 60.0))
+        .or_else(|| offset("h", 3600.0))
+        .or_else(|| parse_clock(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_timed_paragraphs() {
+        let ttml = r#"<tt xmlns="http://www.w3.org/ns/ttml"><body><div>
+<p begin="00:00:01.000" end="00:00:02.500">One<br/>line</p>
+<p begin="3s" dur="1.5s"><span begin="3s">Two</span> <span>words</span></p>
+<p dur="1s">Untimed</p>
+<tt:p begin="5s" end="6s">Three</tt:p>
+</div></body></tt>"#;
+        assert_eq!(
+            parse(ttml),
+            vec![
+                (1.0, 2.5, "One line".to_string()),
+                (3.0, 4.5, "Two words".to_string()),
+                (5.0, 6.0, "Three".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_time_reads_offsets_and_clocks() {
+        assert_eq!(parse_time("1.5s"), Some(1.5));
+        assert_eq!(parse_time("2m"), Some(120.0));
+        assert_eq!(parse_time("01:02.5"), Some(62.5));
+        assert!((parse_time("300ms").unwrap() - 0.3).abs() < 1e-6);
+        assert_eq!(parse_time("soon"), None);
+    }
+}
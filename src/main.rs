@@ -1,214 +1,365 @@
 #![allow(clippy::single_match)]
 
+mod animation;
 mod audio;
+mod check;
+mod cli;
 mod config;
+mod editor;
+mod export;
+mod font;
 mod handle;
+mod headless;
+mod i18n;
+mod lyrics;
+mod player;
+mod search;
+mod theme;
+mod tui;
 mod view;
+mod watch;
 
-use std::time::Duration;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use config::Config;
+use animation::{Animations, Label};
+use clap::Parser;
+use cli::Args;
+use font::FontSet;
 use handle::handle_key;
 use iced::advanced::graphics::core::SmolStr;
 use iced::keyboard::{Key, Modifiers};
-use iced::{executor, keyboard, window, Application, Command, Size, Subscription};
-use iced::{Element, Font, Settings, Theme};
-use rodio::{Sink, Source};
+use iced::multi_window::Application;
+use iced::theme::Palette;
+use iced::widget::image::Handle;
+use iced::{
+    clipboard, event, executor, keyboard, mouse, window, Color, Command, Size, Subscription,
+};
+use iced::{Element, Event, Point, Settings, Theme};
+use player::Player;
+use theme::Themes;
+
+const WINDOW_SIZE: Size = Size::new(1280.0, 720.0);
+const MINI_WINDOW_SIZE: Size = Size::new(640.0, 120.0);
+const CONTROLS_TIMEOUT: Duration = Duration::from_secs(3);
 
 fn main() -> iced::Result {
+    let args = Args::parse();
+    if let Some(cli::Command::CheckConfig) = args.command {
+        std::process::exit(if check::run(&args.config()) { 0 } else { 1 });
+    }
+    if args.no_ui {
+        headless::run(args);
+        return Ok(());
+    }
+    if args.tui {
+        if let Err(err) = tui::run(args) {
+            i18n::fail(i18n::tr().terminal_failed, err);
+        }
+        return Ok(());
+    }
+
+    let player = Player::from(args);
+    let fonts = &player.options.fonts;
     App::run(Settings {
-        fonts: vec![include_bytes!("../MapleMono-NF-CN-Regular.ttf").into()],
-        default_font: Font::with_name("Maple Mono NF CN"),
-        // fonts: vec![include_bytes!("../SarasaMonoTC-Regular.ttf").into()],
-        // default_font: Font::with_name("Sarasa Mono TC"),
+        fonts: font::read_files(fonts),
+        default_font: FontSet::new(fonts).ui,
         antialiasing: true,
         window: window::Settings {
-            size: Size::new(1280.0, 720.0),
+            size: WINDOW_SIZE,
             ..Default::default()
         },
-        ..Default::default()
+        ..Settings::with_flags(player)
     })
 }
 
 struct App {
-    is_prev_playing: bool,
     mode: ViewMode,
-    config: Vec<Config>,
-    sink: Sink,
-    current_pos: usize,
-    current_source: audio::TheSource,
-    slider_value: f32,
-    time: Duration,
-    lang: Lang,
-    tick_secs: f32,
-    speed: f32,
+    player: Player,
+    window_size: Size,
+    window_position: Option<Point>,
+    full_window: Option<(Size, Option<Point>)>,
+    overlay_window: Option<window::Id>,
+    overlay_locked: bool,
+    last_activity: Instant,
+    animations: Animations,
+    themes: Themes,
+    fonts: FontSet,
+    /// Images read again after their file changed, as iced caches images by path.
+    images: HashMap<String, Handle>,
+    watched_dirs: Vec<PathBuf>,
+    /// The error of the last reload of the config or theme file, shown until it is fixed.
+    config_error: Option<String>,
 }
 
-impl Default for App {
-    fn default() -> Self {
-        let sink = audio::new_sink();
-        let config = Config::new("./config.toml");
-        let current_pos = 0;
-        let mode = ViewMode::Play;
-        let lang = Lang::All;
-
-        let source_path = &config[current_pos].source_path;
-        sink.append(audio::new_source(source_path));
-        let current_source = audio::new_source(source_path);
-
-        let time = Duration::ZERO;
-        let slider_value = 0.0;
-        let tick_secs = 0.1;
-        let speed = 1.0;
-
-        // audio::sample(current_source.clone());
-
-        for (idx, i) in config.iter().enumerate() {
-            if idx != current_pos {
-                let source = audio::new_source(&i.source_path);
-                sink.append(source);
+impl App {
+    fn switch_view(&mut self, mode: ViewMode) -> Command<Message> {
+        let prev_mode = std::mem::replace(&mut self.mode, mode);
+        if mode == prev_mode {
+            return Command::none();
+        }
+
+        let mut commands = vec![];
+
+        if prev_mode == ViewMode::Mini {
+            if let Some((size, position)) = self.full_window.take() {
+                commands.push(window::resize(window::Id::MAIN, size));
+                if let Some(position) = position {
+                    commands.push(window::move_to(window::Id::MAIN, position));
+                }
             }
         }
+        if prev_mode == ViewMode::Fullscreen {
+            commands.push(window::change_mode(
+                window::Id::MAIN,
+                window::Mode::Windowed,
+            ));
+        }
 
-        // audio::sample(audio::new_source(source_path));
-
-        Self {
-            is_prev_playing: true,
-            mode,
-            config,
-            sink,
-            current_pos,
-            current_source,
-            time,
-            lang,
-            slider_value,
-            tick_secs,
-            speed,
+        if mode == ViewMode::Mini {
+            self.full_window = Some((self.window_size, self.window_position));
+            commands.push(window::resize(window::Id::MAIN, MINI_WINDOW_SIZE));
+        }
+        if mode == ViewMode::Fullscreen {
+            self.last_activity = Instant::now();
+            commands.push(window::change_mode(
+                window::Id::MAIN,
+                window::Mode::Fullscreen,
+            ));
         }
+
+        Command::batch(commands)
     }
-}
 
-impl App {
-    fn switch_view(&mut self, mode: ViewMode) {
-        self.mode = mode;
+    /// Whether the controls of the fullscreen mode are shown, which are hidden
+    /// after a few seconds without any mouse or keyboard input. The mouse cursor
+    /// stays, as iced 0.12 has no way to hide it.
+    fn controls_visible(&self) -> bool {
+        self.last_activity.elapsed() < CONTROLS_TIMEOUT
     }
 
-    fn update_time(&mut self) {
-        if !self.sink.is_paused() {
-            self.time += Duration::from_secs_f32(self.tick_secs * self.speed);
-            self.slider_value += self.tick_secs;
+    fn toggle_overlay(&mut self) -> Command<Message> {
+        if let Some(id) = self.overlay_window.take() {
+            return window::close(id);
         }
 
-        if !self.sink.is_paused() && self.time >= self.current_source.total_duration().unwrap() {
-            self.next_song();
-        }
+        let config = &self.player.options.overlay;
+        let (id, spawn) = window::spawn(window::Settings {
+            size: Size::new(config.width, config.height),
+            resizable: false,
+            decorations: false,
+            transparent: true,
+            level: window::Level::AlwaysOnTop,
+            ..Default::default()
+        });
+        self.overlay_window = Some(id);
+        spawn
     }
 
-    fn skip_song(&self) {
-        let sink = &self.sink;
-        let source_path = &self.config[self.current_pos].source_path;
-
-        sink.append(audio::new_source(source_path));
+    /// Quits with the main window, and forgets the overlay once the system closed it,
+    /// so toggling it opens a new one.
+    fn window_closed(&mut self, id: window::Id) {
+        if id == window::Id::MAIN {
+            std::process::exit(0);
+        }
+        if Some(id) == self.overlay_window {
+            self.overlay_window = None;
+        }
     }
 
-    fn next_pos(&self) -> usize {
-        let max_pos = self.config.len() - 1;
-        if self.current_pos >= max_pos {
-            0
-        } else {
-            self.current_pos + 1
+    fn image(&self, path: &str) -> Handle {
+        match self.images.get(path) {
+            Some(handle) => handle.clone(),
+            None => Handle::from_path(path),
         }
     }
 
-    fn next_song(&mut self) {
-        self.skip_song();
-        self.sink.play();
-        self.current_pos = self.next_pos();
-        self.time = Duration::ZERO;
-        self.slider_value = 0.0;
+    /// The directories of the config file and of every file it refers to.
+    fn watched_dirs(&self) -> Vec<PathBuf> {
+        let config = self.player.config_path.iter().map(PathBuf::as_path);
+        let config = config.chain(self.themes.file()).filter_map(Path::to_str);
+        let songs = self.player.config.iter().flat_map(|song| {
+            [
+                &song.lyrics_first,
+                &song.lyrics_second,
+                &song.lyrics,
+                &song.avatar,
+                &song.background,
+            ]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+        });
 
-        let source_path = &self.config[self.current_pos].source_path;
-        self.current_source = audio::new_source(source_path);
+        watch::dirs(config.chain(songs))
     }
 
-    // fn prev_pos(&self) -> usize {
-    //     let max_pos = self.config.len() - 1;
-    //     if self.current_pos == 0 {
-    //         max_pos
-    //     } else {
-    //         self.current_pos - 1
-    //     }
-    // }
-
-    // fn prev_song(&mut self) {
-    //     self.skip_song();
-    //     self.current_pos = self.prev_pos();
-    //     self.time = Duration::ZERO;
-    //     self.slider_value = 0.0;
-
-    //     let source_path = &self.config[self.current_pos].source_path;
-    //     self.current_source = audio::new_source(source_path);
-    // }
-
-    fn toggle_play(&mut self) {
-        let sink = &self.sink;
-        if sink.is_paused() {
-            sink.play();
-        } else {
-            sink.pause()
+    fn files_changed(&mut self, paths: Vec<PathBuf>) -> Command<Message> {
+        let changed = |file: &str| paths.contains(&watch::normalize(Path::new(file)));
+
+        let config_path = self
+            .player
+            .config_path
+            .as_ref()
+            .and_then(|path| path.to_str());
+        let theme_file = self.themes.file().and_then(Path::to_str);
+        if config_path.is_some_and(changed) {
+            self.reload_config();
+        } else if theme_file.is_some_and(changed) {
+            self.config_error = self.themes.reload().err();
         }
-        self.is_prev_playing = !sink.is_paused();
-    }
 
-    fn set_volume(&self, relative_factor: i8) {
-        let sink = &self.sink;
-        let volume = ((sink.volume() * 100.0) as i8 + relative_factor).clamp(0, 100);
-        sink.set_volume(volume as f32 / 100.0);
-    }
+        let song = &self.player.config[self.player.current_pos];
+        let lyric_files = [&song.lyrics_first, &song.lyrics_second, &song.lyrics];
+        if lyric_files.into_iter().flatten().any(|file| changed(file)) {
+            self.player.load_lyrics();
+        }
 
-    fn seek_audio(&mut self) {
-        self.time = Duration::from_secs_f32(self.slider_value);
-        self.sink.try_seek(self.time).unwrap();
-    }
+        let images = self
+            .player
+            .config
+            .iter()
+            .flat_map(|song| [&song.avatar, &song.background]);
+        for image in images.flatten().filter(|image| changed(image.as_str())) {
+            if let Ok(bytes) = std::fs::read(image) {
+                self.images
+                    .insert(image.clone(), Handle::from_memory(bytes));
+            }
+        }
 
-    fn toggle_lang(&mut self) {
-        self.lang = match self.lang {
-            Lang::Chinese => Lang::Japanese,
-            Lang::Japanese => Lang::All,
-            Lang::All => Lang::Chinese,
-            _ => unimplemented!(),
+        let avatar = &self.player.config[self.player.current_pos].avatar;
+        if avatar.as_deref().is_some_and(changed) {
+            return self.animations.reload_vinyl();
         }
+        Command::none()
+    }
+
+    fn reload_config(&mut self) {
+        let theme = self.player.options.theme.clone();
+        let reloaded = self.player.reload();
+
+        let themes = if self.player.options.theme != theme {
+            Themes::try_new(self.player.options.theme.as_deref()).map(|themes| self.themes = themes)
+        } else {
+            // the theme file may have been saved along with the config
+            self.themes.reload()
+        };
+        self.config_error = reloaded.and(themes).err();
+        self.watched_dirs = self.watched_dirs();
     }
 
-    fn toggle_speed(&mut self) {
-        self.speed = match self.speed {
-            0.5 => 1.0,
-            1.0 => 1.5,
-            1.5 => 2.0,
-            2.0 => 0.5,
-            _ => unreachable!(),
+    fn handle_message(&mut self, msg: Message) -> Command<Message> {
+        match msg {
+            Message::SwitchView(mode) => {
+                self.player.enter(mode);
+                return self.switch_view(mode);
+            }
+            Message::WindowResized(size) => {
+                // the mini window's own size must not overwrite the size to restore
+                if self.mode != ViewMode::Mini {
+                    self.window_size = size;
+                }
+            }
+            Message::WindowMoved(position) => {
+                if self.mode != ViewMode::Mini {
+                    self.window_position = Some(position);
+                }
+            }
+            Message::WindowClosed(id) => self.window_closed(id),
+            Message::ToggleOverlay => return self.toggle_overlay(),
+            Message::ToggleOverlayLock => self.overlay_locked = !self.overlay_locked,
+            Message::DragOverlay => {
+                if let Some(id) = self.overlay_window {
+                    return window::drag(id);
+                }
+            }
+            Message::Quit => {
+                std::process::exit(0);
+            }
+            Message::Activity => self.last_activity = Instant::now(),
+            Message::NextTheme => {
+                if let Err(err) = self.themes.next() {
+                    self.config_error = Some(err);
+                }
+            }
+            Message::FilesChanged(paths) => return self.files_changed(paths),
+            Message::KeyInput { key, modifiers } => {
+                self.last_activity = Instant::now();
+                let msg = handle_key(self.mode, key, modifiers);
+                return self.handle_message(msg);
+            }
+            Message::SearchPaste => {
+                return clipboard::read(|text| Message::SearchInput(text.unwrap_or_default()));
+            }
+            Message::SearchJump => {
+                self.player.update(Message::SearchJump);
+                return self.handle_message(Message::SwitchView(ViewMode::Play));
+            }
+            Message::Frame(now) => self.animations.tick(now),
+            Message::VinylLoaded(avatar, label) => self.animations.loaded(avatar, label),
+            Message::BackdropLoaded(background, frames) => {
+                self.animations.backdrop.loaded(background, frames)
+            }
+            msg => self.player.update(msg),
         };
-        self.sink.set_speed(self.speed);
+
+        Command::none()
     }
 }
 
 impl Application for App {
     type Executor = executor::Default;
-    type Flags = ();
+    type Flags = Player;
     type Message = Message;
     type Theme = Theme;
 
-    fn new(_flags: ()) -> (Self, Command<Self::Message>) {
-        let app = Self::default();
-        (app, Command::none())
+    fn new(player: Player) -> (Self, Command<Self::Message>) {
+        let overlay_locked = player.options.overlay.locked;
+        let (animations, load) = Animations::new(&player);
+        let themes = Themes::new(player.options.theme.as_deref());
+        let fonts = FontSet::new(&player.options.fonts);
+        let mut app = Self {
+            mode: ViewMode::Play,
+            player,
+            window_size: WINDOW_SIZE,
+            window_position: None,
+            full_window: None,
+            overlay_window: None,
+            overlay_locked,
+            last_activity: Instant::now(),
+            animations,
+            themes,
+            fonts,
+            images: HashMap::new(),
+            watched_dirs: vec![],
+            config_error: None,
+        };
+        app.watched_dirs = app.watched_dirs();
+        (app, load)
     }
 
-    fn title(&self) -> String {
-        "Music player - Iced".into()
+    fn title(&self, window: window::Id) -> String {
+        if Some(window) == self.overlay_window {
+            "Lyrics - Iced".into()
+        } else {
+            "Music player - Iced".into()
+        }
     }
 
-    fn theme(&self) -> Theme {
-        Theme::Nord
+    fn theme(&self, window: window::Id) -> Theme {
+        let palette = self.themes.colors.palette();
+        if Some(window) == self.overlay_window {
+            // the window background is drawn with the theme's background color
+            let palette = Palette {
+                background: Color::TRANSPARENT,
+                ..palette
+            };
+            Theme::custom("Overlay".into(), palette)
+        } else {
+            Theme::custom(self.themes.name(), palette)
+        }
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
@@ -217,52 +368,61 @@ impl Application for App {
             Some(msg)
         });
 
-        let time =
-            iced::time::every(Duration::from_secs_f32(self.tick_secs)).map(|_| Message::UpdateTime);
+        let time = iced::time::every(Duration::from_secs_f32(self.player.tick_secs))
+            .map(|_| Message::UpdateTime);
 
-        Subscription::batch([key, time])
+        let window = event::listen_with(|event, _status| match event {
+            Event::Window(window::Id::MAIN, window::Event::Resized { width, height }) => Some(
+                Message::WindowResized(Size::new(width as f32, height as f32)),
+            ),
+            Event::Window(window::Id::MAIN, window::Event::Moved { x, y }) => {
+                Some(Message::WindowMoved(Point::new(x as f32, y as f32)))
+            }
+            Event::Window(id, window::Event::Closed) => Some(Message::WindowClosed(id)),
+            _ => None,
+        });
+
+        let files = watch::subscription(self.watched_dirs.clone());
+
+        let mut subscriptions = vec![key, time, window, files];
+        let vinyl_shown = self.mode == ViewMode::Play;
+        if self.mode.shows_player() && self.animations.is_running(vinyl_shown) {
+            subscriptions.push(window::frames().map(Message::Frame));
+        }
+        if self.mode == ViewMode::Fullscreen {
+            subscriptions.push(event::listen_with(|event, _status| match event {
+                Event::Mouse(mouse::Event::CursorMoved { .. }) => Some(Message::Activity),
+                _ => None,
+            }));
+        }
+
+        Subscription::batch(subscriptions)
     }
 
     fn update(&mut self, msg: Message) -> Command<Self::Message> {
-        match msg {
-            Message::TogglePlay => self.toggle_play(),
-            Message::ToggleLang => self.toggle_lang(),
-            Message::SetVolume(factor) => self.set_volume(factor),
-            Message::NextSong => {
-                self.next_song();
-                self.sink.skip_one()
-            }
-            // Message::PrevSong => self.prev_song(),
-            Message::SwitchView(mode) => {
-                if mode == ViewMode::Play && self.is_prev_playing {
-                    self.sink.play()
-                } else {
-                    self.sink.pause()
-                }
-                self.switch_view(mode)
-            }
-            Message::Quit => {
-                std::process::exit(0);
-            }
-            Message::KeyInput { key, modifiers } => {
-                let msg = handle_key(self.mode, key, modifiers);
-                return self.update(msg);
-            }
-            Message::UpdateTime => self.update_time(),
-            Message::SeekAudio => self.seek_audio(),
-            Message::UpdateSlider(val) => self.slider_value = val,
-            Message::ToggleSpeed => self.toggle_speed(),
-            _ => (),
+        let command = self.handle_message(msg);
+        // anything may have changed the song, the lyric or the playing state
+        let size = if self.mode == ViewMode::Mini {
+            MINI_WINDOW_SIZE
+        } else {
+            self.window_size
         };
-
-        Command::none()
+        Command::batch([command, self.animations.sync(&self.player, size)])
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self, window: window::Id) -> Element<'_, Message> {
+        if Some(window) == self.overlay_window {
+            return view::lyric_overlay(self);
+        }
+
         match self.mode {
             ViewMode::Play => view::play(self),
+            ViewMode::Mini => view::mini(self),
+            ViewMode::Fullscreen => view::fullscreen(self),
             ViewMode::Help => view::help(self),
             ViewMode::ConfirmQuit => view::confirm_quit(self),
+            ViewMode::LyricEditor => view::lyric_editor(self),
+            ViewMode::Search => view::search(self),
         }
     }
 }
@@ -270,8 +430,26 @@ impl Application for App {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ViewMode {
     Play,
+    Mini,
+    Fullscreen,
     Help,
     ConfirmQuit,
+    LyricEditor,
+    Search,
+}
+
+impl ViewMode {
+    /// Whether the mode shows the player, so playback should go on.
+    fn shows_player(self) -> bool {
+        matches!(
+            self,
+            ViewMode::Play
+                | ViewMode::Mini
+                | ViewMode::Fullscreen
+                | ViewMode::LyricEditor
+                | ViewMode::Search
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -292,13 +470,40 @@ enum Message {
     ToggleSpeed,
     UpdateTime,
     SeekAudio,
+    WindowResized(Size),
+    WindowMoved(Point),
+    WindowClosed(window::Id),
+    ToggleOverlay,
+    ToggleOverlayLock,
+    DragOverlay,
+    Activity,
+    Frame(Instant),
+    VinylLoaded(Option<String>, Option<Arc<Label>>),
+    BackdropLoaded(Option<String>, Vec<Handle>),
+    NextTheme,
+    FilesChanged(Vec<PathBuf>),
+    ShiftLyrics(i64),
+    SaveLyricOffset,
+    ToggleReadings,
+    Stamp,
+    UndoStamp,
+    ReplayLine,
+    NudgeStamp(i64),
+    SaveEditedLyrics,
+    SearchInput(String),
+    SearchErase,
+    SearchSelect(isize),
+    SearchPaste,
+    SearchJump,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Lang {
     All,
     Chinese,
     Japanese,
+    /// Both languages and the original one in Latin letters.
+    Romanized,
     #[allow(dead_code)]
     English,
 }
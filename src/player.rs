@@ -0,0 +1,754 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+use rodio::{source::Amplify, Sink, Source};
+
+use crate::{
+    audio,
+    cli::Args,
+    config::{self, Config, Options},
+    editor::LyricEditor,
+    export::{Exporter, NowPlaying},
+    i18n::{self, tr},
+    lyrics::{self, get_bilingual_lyrics, get_lyrics, ruby, Lyrics},
+    search::Search,
+    Lang, Message, ViewMode,
+};
+
+/// How long before the end of a song the next one is queued, so they play gaplessly.
+const PRELOAD: Duration = Duration::from_secs(2);
+
+/// Playback state shared by every front-end (the iced window, the terminal UI and the headless mode).
+pub struct Player {
+    pub is_prev_playing: bool,
+    pub config: Vec<Config>,
+    pub options: Options,
+    pub sink: Sink,
+    pub current_pos: usize,
+    /// The current song decoded once more, for its duration.
+    pub current_source: Option<audio::TheSource>,
+    pub slider_value: f32,
+    pub time: Duration,
+    pub lang: Lang,
+    pub tick_secs: f32,
+    pub speed: f32,
+    /// The lyrics of the current song.
+    pub lyrics: Lyrics,
+    /// Offset of the lyrics adjusted while playing the current song, in milliseconds.
+    pub lyric_offset: i64,
+    /// The lyric editor, while its mode is shown.
+    pub editor: Option<LyricEditor>,
+    /// The language shown before the lyric editor opened, shown again once it closes.
+    lang_before_editor: Lang,
+    /// The lyric search, while its mode is shown.
+    pub search: Option<Search>,
+    /// Whether the readings of ruby annotations are shown with the lyrics.
+    pub show_readings: bool,
+    /// The error of the last action that failed, such as playing a song, shown
+    /// until the next command.
+    pub error: Option<String>,
+    /// Publishes the song and the lyric lines for streaming overlays. Its server keeps
+    /// the address it was started with when the config file is reloaded.
+    exporter: Exporter,
+    /// The song, language, readings and started lines the exported lines were last
+    /// built from, so they are only built again when one of them changes.
+    exported: Option<(usize, Lang, bool, [usize; 2])>,
+    /// When `time` last moved.
+    ticked: Instant,
+    /// The config file to reload, if the player was started from one.
+    pub config_path: Option<PathBuf>,
+    /// Whether the playlist comes from the config file rather than from the command line.
+    playlist_from_config: bool,
+    /// Whether the next song is already appended to the sink. Only the current song
+    /// is queued before that, so changes to the playlist apply until the last moment.
+    queued: bool,
+    /// Whether the current song was removed from the config file, and so leaves the
+    /// playlist once it ends.
+    removed: bool,
+}
+
+impl From<Args> for Player {
+    fn from(args: Args) -> Self {
+        let sink = audio::new_sink();
+        let (config, options) = Config::load(&args);
+        i18n::init(options.locale);
+        if config.is_empty() {
+            eprintln!("{}", tr().no_songs);
+            std::process::exit(1);
+        }
+        let current_pos = args.index.min(config.len() - 1);
+        let song = &config[current_pos];
+        let lang = song.lang.unwrap_or(Lang::All);
+
+        let current_source = Some(audio::new_source(&song.source_path));
+        let lyrics = Lyrics::load(song, &options.normalize);
+
+        let mut time = args.start.or(song.start).unwrap_or(Duration::ZERO);
+        let tick_secs = 0.1;
+        let speed = args.speed.or(song.speed).unwrap_or(1.0);
+
+        // audio::sample(current_source.clone());
+
+        sink.append(song_source(song).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }));
+
+        // audio::sample(audio::new_source(source_path));
+
+        if let Some(volume) = args.volume {
+            sink.set_volume(volume as f32 / 100.0);
+        }
+        sink.set_speed(speed);
+        // sources that cannot seek play from where `song_source` left them
+        if time != Duration::ZERO && sink.try_seek(time).is_err() {
+            time = song.start.unwrap_or(Duration::ZERO);
+        }
+        if args.paused {
+            sink.pause();
+        }
+
+        let (exporter, error) = Exporter::new(options.export.address.as_deref());
+
+        let config_path = args.config();
+        let config_path = (args.files.is_empty() || config_path.exists()).then_some(config_path);
+
+        Self {
+            is_prev_playing: !args.paused,
+            config,
+            options,
+            sink,
+            current_pos,
+            current_source,
+            time,
+            lang,
+            slider_value: time.as_secs_f32(),
+            tick_secs,
+            speed,
+            lyrics,
+            lyric_offset: 0,
+            editor: None,
+            lang_before_editor: lang,
+            search: None,
+            show_readings: true,
+            error,
+            exporter,
+            exported: None,
+            ticked: Instant::now(),
+            config_path,
+            playlist_from_config: args.files.is_empty(),
+            queued: false,
+            removed: false,
+        }
+    }
+}
+
+impl Player {
+    /// Handles the messages that affect playback; messages that only concern a front-end are ignored.
+    pub fn update(&mut self, msg: Message) {
+        if !matches!(msg, Message::UpdateTime) {
+            self.error = None;
+        }
+        match msg {
+            Message::TogglePlay => self.toggle_play(),
+            Message::ToggleLang => self.toggle_lang(),
+            Message::SetVolume(factor) => self.set_volume(factor),
+            Message::NextSong => {
+                if !self.queued {
+                    self.queue_next();
+                }
+                self.sink.skip_one();
+                self.next_song();
+            }
+            Message::PrevSong => self.play_at(self.prev_pos(), Duration::ZERO),
+            Message::UpdateTime => {
+                self.update_time();
+                self.export();
+            }
+            Message::SeekAudio => self.seek_audio(),
+            Message::UpdateSlider(val) => self.slider_value = val,
+            Message::ToggleSpeed => self.toggle_speed(),
+            Message::ShiftLyrics(ms) => self.lyric_offset += ms,
+            Message::SaveLyricOffset => self.save_lyric_offset(),
+            Message::ToggleReadings => self.show_readings = !self.show_readings,
+            Message::Stamp => {
+                let time = self.position();
+                if let Some(editor) = &mut self.editor {
+                    editor.stamp(time);
+                }
+            }
+            Message::UndoStamp => {
+                if let Some(time) = self.editor.as_mut().map(LyricEditor::undo) {
+                    self.replay_from(time);
+                }
+            }
+            Message::ReplayLine => {
+                if let Some(time) = self.editor.as_ref().map(LyricEditor::replay_time) {
+                    self.replay_from(time);
+                }
+            }
+            Message::NudgeStamp(ms) => {
+                if let Some(editor) = &mut self.editor {
+                    editor.nudge(ms);
+                }
+            }
+            Message::SaveEditedLyrics => {
+                self.save_editor();
+                self.load_lyrics();
+            }
+            Message::SearchInput(text) => {
+                if let Some(search) = &mut self.search {
+                    search.push(&text);
+                }
+            }
+            Message::SearchErase => {
+                if let Some(search) = &mut self.search {
+                    search.erase();
+                }
+            }
+            Message::SearchSelect(step) => {
+                if let Some(search) = &mut self.search {
+                    search.select(step);
+                }
+            }
+            Message::SearchJump => {
+                if let Some(hit) = self.search.as_ref().and_then(Search::selected).cloned() {
+                    self.play_at(hit.song, Duration::from_secs_f32(hit.time));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Pauses playback while `mode` covers the player, and opens the lyric editor
+    /// or the lyric search when entering their modes.
+    pub fn enter(&mut self, mode: ViewMode) {
+        if mode == ViewMode::LyricEditor {
+            let lang = match self.lang {
+                Lang::Japanese => Lang::Japanese,
+                _ => Lang::Chinese,
+            };
+            self.open_editor(lang);
+        } else if self.editor.take().is_some() {
+            self.lang = self.lang_before_editor;
+        }
+        self.search = (mode == ViewMode::Search).then(|| Search::new(&self.config, &self.options));
+        self.suspend(!mode.shows_player());
+    }
+
+    /// Pauses playback while another view covers the player, and resumes it
+    /// afterwards if it was playing before.
+    fn suspend(&self, suspend: bool) {
+        if !suspend && self.is_prev_playing {
+            self.sink.play()
+        } else {
+            self.sink.pause()
+        }
+    }
+
+    /// Reloads the config file, updating the playlist without interrupting the current song.
+    pub fn reload(&mut self) -> Result<(), String> {
+        let Some(path) = &self.config_path else {
+            return Ok(());
+        };
+        let (config, options) = Config::try_new(path)?;
+        if self.playlist_from_config {
+            // the songs already in the playlist were decoded when they were added
+            let added = config.iter().filter(|song| {
+                !self
+                    .config
+                    .iter()
+                    .any(|old| old.source_path == song.source_path)
+            });
+            for song in added {
+                audio::try_new_source(&song.source_path)?;
+            }
+        }
+
+        self.options = options;
+        if self.playlist_from_config {
+            self.set_playlist(config);
+        }
+        self.load_lyrics();
+        Ok(())
+    }
+
+    /// Reads the lyrics of the current song again, after the config or their files changed.
+    pub fn load_lyrics(&mut self) {
+        self.lyrics = Lyrics::load(&self.config[self.current_pos], &self.options.normalize);
+        self.exported = None;
+    }
+
+    /// The duration of the current song up to its `end`, unknown for streamed and some
+    /// variable bitrate sources without one.
+    pub fn total_duration(&self) -> Option<Duration> {
+        let total = self
+            .current_source
+            .as_ref()
+            .and_then(Source::total_duration);
+        match (total, self.config[self.current_pos].end) {
+            (Some(total), Some(end)) => Some(total.min(end)),
+            (total, end) => total.or(end),
+        }
+    }
+
+    /// Whether the clock reached the end of the current song, when it is known.
+    fn at_end(&self, ahead: Duration) -> bool {
+        self.total_duration()
+            .is_some_and(|total| self.time + ahead >= total)
+    }
+
+    pub fn time_text(&self) -> String {
+        let get_time = |secs| (secs / 60, secs % 60);
+
+        let (minute, second) = get_time(self.time.as_secs());
+        let total = match self.total_duration() {
+            Some(total) => {
+                let (total_minute, total_second) = get_time(total.as_secs());
+                format!("{}m{}s", total_minute, total_second)
+            }
+            None => "--".to_string(),
+        };
+
+        let played = tr().played;
+        if minute != 0 {
+            format!("{}: {}m{}s/{}", played, minute, second, total)
+        } else {
+            format!("{}: {}s/{}", played, second, total)
+        }
+    }
+
+    /// The playback position, more precise than `time` which only moves every tick.
+    pub fn position(&self) -> Duration {
+        if self.sink.is_paused() {
+            return self.time;
+        }
+        let elapsed = self
+            .ticked
+            .elapsed()
+            .min(Duration::from_secs_f32(self.tick_secs));
+        self.time + elapsed.mul_f32(self.speed)
+    }
+
+    pub fn offset_text(&self) -> String {
+        format!("{}: {:+}ms", tr().lyric_offset, self.lyric_offset)
+    }
+
+    /// The languages selected by `lang`, in the order their lyrics are shown.
+    pub fn langs(&self) -> Vec<Lang> {
+        match self.lang {
+            Lang::All => vec![Lang::Chinese, Lang::Japanese],
+            Lang::Romanized => vec![Lang::Chinese, Lang::Japanese, Lang::Romanized],
+            single_lang => vec![single_lang],
+        }
+    }
+
+    /// The current lyric line of every language selected by `lang`.
+    pub fn current_lyrics(&self) -> Vec<String> {
+        if let Lang::All | Lang::Romanized = self.lang {
+            let (chinese, japanese) = get_bilingual_lyrics(self);
+            let mut lines = vec![chinese.unwrap_or_default(), japanese.unwrap_or_default()];
+            if self.lang == Lang::Romanized {
+                let original = self.config[self.current_pos].original();
+                let line = match original {
+                    Lang::Chinese => &lines[0],
+                    _ => &lines[1],
+                };
+                lines.push(lyrics::romanize(line, original));
+            }
+            return lines;
+        }
+
+        self.langs()
+            .into_iter()
+            .map(|lang| get_lyrics(self, lang).unwrap_or_default())
+            .collect()
+    }
+
+    /// Publishes the song and its current lyric lines, if they are exported.
+    fn export(&mut self) {
+        let file = self.options.export.file.as_deref();
+        if file.is_none() && !self.exporter.is_serving() {
+            return;
+        }
+        let state = (
+            self.current_pos,
+            self.lang,
+            self.show_readings,
+            lyrics::started_lines(self),
+        );
+        if self.exported == Some(state) {
+            return;
+        }
+        self.exported = Some(state);
+
+        let song = &self.config[self.current_pos];
+        let lines = self
+            .current_lyrics()
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| ruby::inline(line, self.show_readings))
+            .collect();
+        let now = NowPlaying {
+            title: song.name.clone(),
+            artist: song.artist.clone().unwrap_or_default(),
+            lines,
+        };
+        if let Err(err) = self.exporter.publish(now, file) {
+            self.error = Some(err);
+        }
+    }
+
+    fn update_time(&mut self) {
+        if !self.sink.is_paused() {
+            self.time += Duration::from_secs_f32(self.tick_secs * self.speed);
+            self.slider_value += self.tick_secs;
+        }
+        self.ticked = Instant::now();
+
+        // the lyric editor keeps to the song it edits
+        if self.editor.is_some() {
+            if self.at_end(Duration::ZERO) {
+                self.sink.pause();
+            }
+            return;
+        }
+
+        // without a known duration the next song is only queued once the sink runs out
+        if !self.queued && (self.at_end(PRELOAD) || self.sink.empty()) {
+            self.queue_next();
+        }
+        // the sink moved on to the queued song, or the current one reached its `end`
+        let ended = self.config[self.current_pos].end.is_some() && self.at_end(Duration::ZERO);
+        if self.queued && (ended || self.sink.len() <= 1) {
+            if self.sink.len() > 1 {
+                self.sink.skip_one();
+            }
+            self.next_song();
+        }
+    }
+
+    /// Appends the next song to the sink, leaving out of the playlist the songs
+    /// that cannot be played anymore.
+    fn queue_next(&mut self) {
+        loop {
+            let pos = self.next_pos();
+            match song_source(&self.config[pos]) {
+                Ok(source) => {
+                    self.sink.append(source);
+                    self.queued = true;
+                    return;
+                }
+                // the current song stays, to be tried again on the next tick
+                Err(err) if pos == self.current_pos => {
+                    self.error = Some(err);
+                    return;
+                }
+                Err(err) => {
+                    self.error = Some(err);
+                    self.config.remove(pos);
+                    if pos < self.current_pos {
+                        self.current_pos -= 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replaces the playlist, keeping the current song in it even if it was removed
+    /// so it plays until its end.
+    fn set_playlist(&mut self, mut config: Vec<Config>) {
+        let queued = self.queued.then(|| self.next_source());
+        let old = std::mem::take(&mut self.config);
+        let current = old.into_iter().nth(self.current_pos).unwrap();
+
+        let pos = config
+            .iter()
+            .position(|song| song.source_path == current.source_path);
+        self.removed = pos.is_none();
+        match pos {
+            Some(pos) => self.current_pos = pos,
+            None => {
+                self.current_pos = self.current_pos.min(config.len());
+                config.insert(self.current_pos, current);
+            }
+        }
+        self.config = config;
+
+        if queued.is_some_and(|queued| queued != self.next_source()) {
+            self.requeue();
+        }
+    }
+
+    /// What `queue_next` appends to the sink: the file of the next song, and where
+    /// and how loud it starts.
+    fn next_source(&self) -> (String, Option<Duration>, f32) {
+        let song = &self.config[self.next_pos()];
+        (song.source_path.clone(), song.start, song.amplitude())
+    }
+
+    /// Drops the song queued after the current one, which the sink cannot remove on
+    /// its own, so the next one is queued again from the new playlist.
+    fn requeue(&mut self) {
+        let paused = self.sink.is_paused();
+        self.sink.clear();
+        self.replay_from(Some(self.time));
+        if paused {
+            self.sink.pause();
+        }
+    }
+
+    fn next_pos(&self) -> usize {
+        let max_pos = self.config.len() - 1;
+        if self.current_pos >= max_pos {
+            0
+        } else {
+            self.current_pos + 1
+        }
+    }
+
+    fn prev_pos(&self) -> usize {
+        if self.current_pos == 0 {
+            self.config.len() - 1
+        } else {
+            self.current_pos - 1
+        }
+    }
+
+    fn next_song(&mut self) {
+        self.queued = false;
+        self.sink.play();
+        if self.removed && self.config.len() > 1 {
+            // the next song takes the place of the removed one
+            self.config.remove(self.current_pos);
+            if self.current_pos >= self.config.len() {
+                self.current_pos = 0;
+            }
+        } else {
+            self.current_pos = self.next_pos();
+        }
+        self.removed = false;
+        self.reset_song();
+    }
+
+    /// Plays the song at `pos` of the playlist from `time`, dropping the songs queued.
+    fn play_at(&mut self, mut pos: usize, time: Duration) {
+        if pos >= self.config.len() {
+            return;
+        }
+        let source = match song_source(&self.config[pos]) {
+            Ok(source) => source,
+            Err(err) => {
+                self.error = Some(err);
+                return;
+            }
+        };
+        if self.removed && pos != self.current_pos {
+            self.config.remove(self.current_pos);
+            if pos > self.current_pos {
+                pos -= 1;
+            }
+        }
+        self.removed = false;
+        self.current_pos = pos;
+        self.reset_song();
+        // not into the trimmed start of the song
+        let time = time.max(self.time);
+
+        self.sink.clear();
+        self.sink.append(source);
+        self.queued = false;
+        self.sink.play();
+        self.is_prev_playing = true;
+        self.seek_to(time);
+    }
+
+    /// Sets up the player for the current song, from its start.
+    fn reset_song(&mut self) {
+        self.lyric_offset = 0;
+
+        let song = &self.config[self.current_pos];
+        self.time = song.start.unwrap_or(Duration::ZERO);
+        self.slider_value = self.time.as_secs_f32();
+        self.current_source = audio::try_new_source(&song.source_path).ok();
+        self.lyrics = Lyrics::load(song, &self.options.normalize);
+        self.exported = None;
+
+        if let Some(speed) = song.speed {
+            self.speed = speed;
+            self.sink.set_speed(speed);
+        }
+        if let Some(lang) = song.lang {
+            self.lang = lang;
+        }
+    }
+
+    fn toggle_play(&mut self) {
+        let sink = &self.sink;
+        if sink.is_paused() {
+            sink.play();
+        } else {
+            sink.pause()
+        }
+        self.is_prev_playing = !sink.is_paused();
+    }
+
+    fn set_volume(&self, relative_factor: i8) {
+        let sink = &self.sink;
+        let volume = ((sink.volume() * 100.0) as i8 + relative_factor).clamp(0, 100);
+        sink.set_volume(volume as f32 / 100.0);
+    }
+
+    fn seek_audio(&mut self) {
+        self.seek_to(Duration::from_secs_f32(self.slider_value));
+    }
+
+    /// Moves the sink and the clock to `time`. Sources that cannot seek keep playing
+    /// from where they are.
+    fn seek_to(&mut self, time: Duration) {
+        if self.sink.try_seek(time).is_ok() {
+            self.time = time;
+        }
+        self.slider_value = self.time.as_secs_f32();
+        self.ticked = Instant::now();
+    }
+
+    fn open_editor(&mut self, lang: Lang) {
+        if self.editor.is_none() {
+            self.lang_before_editor = self.lang;
+        }
+        self.lang = lang;
+        self.editor = Some(LyricEditor::open(&self.config[self.current_pos], lang));
+        self.is_prev_playing = true;
+        self.replay_from(None);
+    }
+
+    /// Plays the current song again from `time`, or from its start, even after the
+    /// sink moved past it.
+    fn replay_from(&mut self, time: Option<Duration>) {
+        let song = &self.config[self.current_pos];
+        let time = time.or(song.start).unwrap_or(Duration::ZERO);
+        if self.sink.empty() || (self.queued && self.sink.len() <= 1) {
+            let source = match song_source(song) {
+                Ok(source) => source,
+                Err(err) => {
+                    self.error = Some(err);
+                    return;
+                }
+            };
+            self.sink.clear();
+            self.sink.append(source);
+            self.queued = false;
+            self.time = song.start.unwrap_or(Duration::ZERO);
+        }
+        self.sink.play();
+        self.seek_to(time);
+    }
+
+    /// Saves the lyric editor to its LRC file, and adds the file to the song if it
+    /// had no lyrics of that language.
+    fn save_editor(&mut self) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        if !editor.save() {
+            return;
+        }
+
+        let song = &mut self.config[self.current_pos];
+        let (lyrics, key) = match editor.lang {
+            Lang::Japanese => (&mut song.lyrics_second, "lyrics_second"),
+            _ => (&mut song.lyrics_first, "lyrics_first"),
+        };
+        if lyrics.is_some() {
+            return;
+        }
+        let path = editor.path.to_string_lossy().into_owned();
+        *lyrics = Some(path.clone());
+
+        let Some(config_path) = self
+            .config_path
+            .as_ref()
+            .filter(|_| self.playlist_from_config)
+        else {
+            return;
+        };
+        if let Err(err) = config::add_lyrics(config_path, &song.source_path, key, &path) {
+            editor.message = Some(format!(
+                "{} {}: {err}",
+                tr().update_config_failed,
+                config_path.display()
+            ));
+        }
+    }
+
+    fn toggle_lang(&mut self) {
+        // the editor switches between the tracks of the song
+        if let Some(editor) = &self.editor {
+            let lang = match editor.lang {
+                Lang::Chinese => Lang::Japanese,
+                _ => Lang::Chinese,
+            };
+            self.open_editor(lang);
+            return;
+        }
+
+        self.lang = match self.lang {
+            Lang::Chinese => Lang::Japanese,
+            Lang::Japanese => Lang::All,
+            Lang::All if self.options.romanization => Lang::Romanized,
+            Lang::All | Lang::Romanized | Lang::English => Lang::Chinese,
+        }
+    }
+
+    /// Saves the offset adjusted while playing to the lyrics files shown, which then
+    /// no longer need it.
+    fn save_lyric_offset(&mut self) {
+        let song = &self.config[self.current_pos];
+        let mut saved = true;
+        // both languages may come from the same combined file
+        let paths = self
+            .langs()
+            .into_iter()
+            // the romanization has no file of its own
+            .filter(|lang| *lang != Lang::Romanized)
+            .filter_map(|lang| song.lyrics_file(lang))
+            .map(|(path, _)| path)
+            .unique();
+        for path in paths {
+            if let Err(err) = lyrics::save_offset(path, self.lyric_offset, self.options.save_offset)
+            {
+                self.error = Some(format!("{} {path}: {err}", tr().save_lyrics_failed));
+                saved = false;
+            }
+        }
+        if saved {
+            self.lyric_offset = 0;
+        }
+        self.load_lyrics();
+    }
+
+    fn toggle_speed(&mut self) {
+        self.speed = match self.speed {
+            0.5 => 1.0,
+            1.0 => 1.5,
+            1.5 => 2.0,
+            2.0 => 0.5,
+            _ => 1.0,
+        };
+        self.sink.set_speed(self.speed);
+    }
+}
+
+/// The audio of `song`, starting at its `start` and with its gain applied.
+fn song_source(song: &Config) -> Result<Amplify<audio::TheSource>, String> {
+    let mut source = audio::try_new_source(&song.source_path)?;
+    if let Some(start) = song.start {
+        // sources that cannot seek play from their beginning
+        source.try_seek(start).ok();
+    }
+    Ok(source.amplify(song.amplitude()))
+}
@@ -0,0 +1,98 @@
+use crate::{
+    config::{Config, Options},
+    lyrics::{self, ruby},
+    Lang,
+};
+
+#[derive(Clone)]
+pub struct Hit {
+    pub song: usize,
+    pub lang: Lang,
+    pub time: f32,
+    pub line: String,
+}
+
+/// Search of the lyrics of every song of the playlist.
+pub struct Search {
+    pub query: String,
+    pub hits: Vec<Hit>,
+    pub selected: usize,
+    lines: Vec<Hit>,
+}
+
+impl Search {
+    pub fn new(songs: &[Config], options: &Options) -> Self {
+        let mut lines = vec![];
+        for (pos, song) in songs.iter().enumerate() {
+            for lang in [Lang::Chinese, Lang::Japanese] {
+                let Ok(track) = lyrics::song_track(song, lang, &options.normalize) else {
+                    continue;
+                };
+                let track = track
+                    .into_iter()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(time, line)| (time.max(0.0), line));
+                for (time, line) in track {
+                    let romanized = (options.romanization && lang == song.original())
+                        .then(|| lyrics::romanize(&line, lang));
+                    lines.push(Hit {
+                        song: pos,
+                        lang,
+                        time,
+                        line: ruby::plain(&line),
+                    });
+                    lines.extend(romanized.map(|line| Hit {
+                        song: pos,
+                        lang: Lang::Romanized,
+                        time,
+                        line,
+                    }));
+                }
+            }
+        }
+
+        Self {
+            query: String::new(),
+            hits: vec![],
+            selected: 0,
+            lines,
+        }
+    }
+
+    pub fn push(&mut self, text: &str) {
+        self.query.extend(text.chars().filter(|c| !c.is_control()));
+        self.update();
+    }
+
+    pub fn erase(&mut self) {
+        self.query.pop();
+        self.update();
+    }
+
+    pub fn select(&mut self, step: isize) {
+        let last = self.hits.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(step).min(last);
+    }
+
+    pub fn selected(&self) -> Option<&Hit> {
+        self.hits.get(self.selected)
+    }
+
+    fn update(&mut self) {
+        self.selected = 0;
+        let query = self.query.trim().to_lowercase();
+        if query.is_empty() {
+            self.hits.clear();
+            return;
+        }
+
+        self.hits = self
+            .lines
+            .iter()
+            .filter(|hit| hit.line.to_lowercase().contains(&query))
+            .cloned()
+            .collect();
+        self.hits
+            .sort_by(|a, b| a.song.cmp(&b.song).then(a.time.total_cmp(&b.time)));
+    }
+}
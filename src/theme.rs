@@ -0,0 +1,212 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use iced::{theme::Palette, Color};
+use serde::{de::Error, Deserialize, Deserializer};
+
+use crate::i18n::tr;
+
+const BUNDLED: &[(&str, &str)] = &[
+    ("default", include_str!("../themes/default.toml")),
+    ("light", include_str!("../themes/light.toml")),
+    ("dark", include_str!("../themes/dark.toml")),
+];
+
+/// The colors of a theme file, see `themes/default.toml` for what each one is used for.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Colors {
+    #[serde(deserialize_with = "color")]
+    pub background: Color,
+    #[serde(deserialize_with = "color")]
+    pub text: Color,
+    #[serde(deserialize_with = "color")]
+    pub accent: Color,
+    #[serde(deserialize_with = "color")]
+    pub lyric_active: Color,
+    #[serde(deserialize_with = "color")]
+    pub lyric_inactive: Color,
+    #[serde(deserialize_with = "color")]
+    pub slider_rail: Color,
+    #[serde(deserialize_with = "color")]
+    pub slider_progress: Color,
+    #[serde(deserialize_with = "color")]
+    pub slider_handle: Color,
+    #[serde(deserialize_with = "color")]
+    pub slider_handle_border: Color,
+    #[serde(deserialize_with = "color")]
+    pub button: Color,
+    #[serde(deserialize_with = "color")]
+    pub overlay_backdrop: Color,
+    #[serde(deserialize_with = "color")]
+    pub background_dim: Color,
+}
+
+impl Colors {
+    /// The palette of the iced widgets not styled by the views themselves.
+    pub fn palette(&self) -> Palette {
+        Palette {
+            background: self.background,
+            text: self.accent,
+            primary: self.slider_progress,
+            ..Palette::NORD
+        }
+    }
+}
+
+enum Source {
+    Bundled(&'static str, &'static str),
+    File(PathBuf),
+}
+
+impl Source {
+    fn name(&self) -> String {
+        match self {
+            Source::Bundled(name, _) => name.to_string(),
+            Source::File(path) => path.to_string_lossy().into_owned(),
+        }
+    }
+}
+
+/// The bundled themes plus the theme file of the user, if any, switched through
+/// at runtime.
+pub struct Themes {
+    sources: Vec<Source>,
+    current: usize,
+    modified: Option<SystemTime>,
+    pub colors: Colors,
+}
+
+impl Themes {
+    /// Selects the bundled theme called `selected`, or loads it as a theme file.
+    pub fn new(selected: Option<&str>) -> Self {
+        Self::try_new(selected).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            std::process::exit(1);
+        })
+    }
+
+    /// Like [`Themes::new`], but returns the error message instead of exiting.
+    pub fn try_new(selected: Option<&str>) -> Result<Self, String> {
+        let mut sources = BUNDLED
+            .iter()
+            .map(|&(name, content)| Source::Bundled(name, content))
+            .collect::<Vec<_>>();
+
+        let selected = selected.unwrap_or("default");
+        let current = match BUNDLED.iter().position(|(name, _)| *name == selected) {
+            Some(idx) => idx,
+            None => {
+                sources.push(Source::File(selected.into()));
+                sources.len() - 1
+            }
+        };
+
+        let source = &sources[current];
+        let colors = load(source)
+            .map_err(|err| format!("{} {}: {err}", tr().load_theme_failed, source.name()))?;
+
+        Ok(Self {
+            modified: modified(source),
+            sources,
+            current,
+            colors,
+        })
+    }
+
+    pub fn name(&self) -> String {
+        self.sources[self.current].name()
+    }
+
+    /// Switches to the next theme, skipping the ones that fail to load, whose
+    /// error is returned.
+    pub fn next(&mut self) -> Result<(), String> {
+        let mut result = Ok(());
+        for _ in 0..self.sources.len() {
+            self.current = (self.current + 1) % self.sources.len();
+            let source = &self.sources[self.current];
+            match load(source) {
+                Ok(colors) => {
+                    self.colors = colors;
+                    self.modified = modified(source);
+                    break;
+                }
+                Err(err) => {
+                    result = Err(format!(
+                        "{} {}: {err}",
+                        tr().load_theme_failed,
+                        source.name()
+                    ))
+                }
+            }
+        }
+        result
+    }
+
+    /// Reloads the current theme file if it changed since it was loaded, keeping
+    /// the previous colors while the file is invalid.
+    pub fn reload(&mut self) -> Result<(), String> {
+        let source = &self.sources[self.current];
+        let modified = modified(source);
+        if modified == self.modified {
+            return Ok(());
+        }
+
+        self.modified = modified;
+        self.colors = load(source)
+            .map_err(|err| format!("{} {}: {err}", tr().load_theme_failed, source.name()))?;
+        Ok(())
+    }
+
+    /// The theme file of the user, which is reloaded whenever it changes.
+    pub fn file(&self) -> Option<&Path> {
+        self.sources.iter().find_map(|source| match source {
+            Source::File(path) => Some(path.as_path()),
+            Source::Bundled(..) => None,
+        })
+    }
+}
+
+/// Whether `name` is one of the bundled themes rather than a theme file.
+pub fn is_bundled(name: &str) -> bool {
+    BUNDLED.iter().any(|(bundled, _)| *bundled == name)
+}
+
+/// Parses a `#rrggbb` or `#rrggbbaa` color.
+pub fn hex(s: &str) -> Option<Color> {
+    let s = s.strip_prefix('#')?;
+    let channel = |i: usize| u8::from_str_radix(s.get(i..i + 2)?, 16).ok();
+
+    match s.len() {
+        6 => Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?)),
+        8 => Some(Color::from_rgba8(
+            channel(0)?,
+            channel(2)?,
+            channel(4)?,
+            channel(6)? as f32 / 255.0,
+        )),
+        _ => None,
+    }
+}
+
+fn load(source: &Source) -> Result<Colors, String> {
+    let content = match source {
+        Source::Bundled(_, content) => content.to_string(),
+        Source::File(path) => fs::read_to_string(path).map_err(|err| err.to_string())?,
+    };
+    toml::from_str(&content).map_err(|err| err.to_string())
+}
+
+fn modified(source: &Source) -> Option<SystemTime> {
+    match source {
+        Source::Bundled(..) => None,
+        Source::File(path) => fs::metadata(path).and_then(|meta| meta.modified()).ok(),
+    }
+}
+
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    hex(&s).ok_or_else(|| D::Error::custom(format!("invalid color `{s}`")))
+}
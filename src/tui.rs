@@ -0,0 +1,302 @@
+use std::{
+    io::{self, Stdout},
+    panic,
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use iced::{
+    advanced::graphics::core::SmolStr,
+    keyboard::{key::Named, Key, Modifiers},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Borders, Gauge, List, ListState, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    cli::Args, handle::handle_key, i18n::tr, lyrics, player::Player, view, Message, ViewMode,
+};
+
+struct Tui {
+    mode: ViewMode,
+    player: Player,
+}
+
+/// Runs the terminal front-end until the user confirms quitting.
+pub fn run(args: Args) -> io::Result<()> {
+    let mut tui = Tui {
+        mode: ViewMode::Play,
+        player: Player::from(args),
+    };
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+
+    // a panic still leaves the terminal usable for its message
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        hook(info);
+    }));
+
+    let result = tui.run(&mut terminal);
+
+    restore()?;
+    result
+}
+
+fn restore() -> io::Result<()> {
+    terminal::disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)
+}
+
+impl Tui {
+    fn run(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+        let tick = Duration::from_secs_f32(self.player.tick_secs);
+        let mut last_tick = Instant::now();
+
+        loop {
+            terminal.draw(|frame| self.view(frame))?;
+
+            let timeout = tick.saturating_sub(last_tick.elapsed());
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    let Some(key) = to_iced_key(key.code) else {
+                        continue;
+                    };
+
+                    match handle_key(self.mode, key, Modifiers::default()) {
+                        Message::Quit => return Ok(()),
+                        // the window-only views and the overlay have no terminal counterpart
+                        Message::SwitchView(ViewMode::Mini | ViewMode::Fullscreen)
+                        | Message::ToggleOverlay
+                        | Message::ToggleOverlayLock
+                        | Message::NextTheme => {}
+                        Message::SwitchView(mode) => {
+                            self.player.enter(mode);
+                            self.mode = mode;
+                        }
+                        Message::SearchJump => {
+                            self.player.update(Message::SearchJump);
+                            self.player.enter(ViewMode::Play);
+                            self.mode = ViewMode::Play;
+                        }
+                        msg => self.player.update(msg),
+                    }
+                }
+            }
+
+            if last_tick.elapsed() >= tick {
+                self.player.update(Message::UpdateTime);
+                last_tick = Instant::now();
+            }
+        }
+    }
+
+    fn view(&self, frame: &mut Frame) {
+        match self.mode {
+            ViewMode::Play | ViewMode::Mini | ViewMode::Fullscreen => self.play(frame),
+            ViewMode::Help => help(frame),
+            ViewMode::ConfirmQuit => confirm_quit(frame),
+            ViewMode::LyricEditor => self.lyric_editor(frame),
+            ViewMode::Search => self.search(frame),
+        }
+    }
+
+    fn play(&self, frame: &mut Frame) {
+        let player = &self.player;
+        let [list_area, main_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Fill(1)])
+                .areas(frame.size());
+
+        let songs = List::new(player.config.iter().map(|config| config.name.as_str()))
+            .block(Block::default().borders(Borders::ALL).title(tr().songs))
+            .highlight_style(Style::new().bold().reversed());
+        let mut state = ListState::default().with_selected(Some(player.current_pos));
+        frame.render_stateful_widget(songs, list_area, &mut state);
+
+        let main = Block::default().borders(Borders::ALL);
+        let inner = main.inner(main_area);
+        frame.render_widget(main, main_area);
+
+        let [name_area, status_area, progress_area, lyric_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        let name = Paragraph::new(player.config[player.current_pos].name.as_str())
+            .bold()
+            .centered();
+        frame.render_widget(name, name_area);
+
+        let tr = tr();
+        let status = format!(
+            "{}    {}: {}%    {}: {:.1}    {}    {}",
+            player.time_text(),
+            tr.volume,
+            (player.sink.volume() * 100.0) as i8,
+            tr.speed,
+            player.sink.speed(),
+            player.offset_text(),
+            tr.play_state(player.sink.is_paused()),
+        );
+        let mut status = vec![Line::from(status)];
+        if let Some(error) = &player.error {
+            status.push(Line::from(error.as_str()).red());
+        }
+        frame.render_widget(Paragraph::new(status).centered(), status_area);
+
+        let ratio = player.total_duration().map_or(0.0, |total| {
+            (player.time.as_secs_f64() / total.as_secs_f64()).clamp(0.0, 1.0)
+        });
+        let progress = Gauge::default()
+            .ratio(ratio)
+            .label("")
+            .gauge_style(Style::new().cyan());
+        frame.render_widget(progress, padded(progress_area, 4));
+
+        let mut lyric = player
+            .current_lyrics()
+            .into_iter()
+            .map(|line| Line::from(lyrics::ruby::inline(&line, player.show_readings)))
+            .collect::<Vec<_>>();
+        if let Some(error) = &player.lyrics.error {
+            lyric.insert(0, Line::from(error.as_str()).red());
+        }
+        let lyric = Paragraph::new(lyric).bold().centered();
+        frame.render_widget(lyric, padded(lyric_area, 1));
+    }
+
+    fn lyric_editor(&self, frame: &mut Frame) {
+        let player = &self.player;
+        let Some(editor) = &player.editor else {
+            return;
+        };
+
+        let title = format!(
+            "{} - {}",
+            tr().lyric_editor,
+            player.config[player.current_pos].name
+        );
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(frame.size());
+        frame.render_widget(block, frame.size());
+
+        let [status_area, lines_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(inner);
+        let status = vec![
+            Line::from(editor.path.display().to_string()),
+            Line::from(player.time_text()),
+            Line::from(editor.message.clone().unwrap_or_default()),
+        ];
+        frame.render_widget(Paragraph::new(status), padded(status_area, 1));
+
+        let current = editor.current();
+        let lines = editor.lines.iter().enumerate().map(|(idx, line)| {
+            let time = match editor.times.get(idx) {
+                Some(time) => lyrics::format_time(time.as_secs_f32()),
+                None => "[--:--.--]".to_string(),
+            };
+            format!("{time}  {line}")
+        });
+        let lines = List::new(lines).highlight_style(Style::new().bold().reversed());
+        let mut state = ListState::default().with_selected(Some(current));
+        frame.render_stateful_widget(lines, padded(lines_area, 1), &mut state);
+    }
+
+    fn search(&self, frame: &mut Frame) {
+        let player = &self.player;
+        let Some(search) = &player.search else {
+            return;
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(tr().lyric_search);
+        let inner = block.inner(frame.size());
+        frame.render_widget(block, frame.size());
+
+        let [query_area, hits_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+        let query = if !search.query.trim().is_empty() && search.hits.is_empty() {
+            format!("> {}    {}", search.query, tr().no_results)
+        } else {
+            format!("> {}", search.query)
+        };
+        frame.render_widget(Paragraph::new(query).bold(), padded(query_area, 1));
+
+        let hits = search.hits.iter().map(|hit| {
+            let name = player
+                .config
+                .get(hit.song)
+                .map_or("", |song| song.name.as_str());
+            let time = lyrics::format_time(hit.time);
+            format!("{name}  {time}  {}", hit.line)
+        });
+        let hits = List::new(hits).highlight_style(Style::new().bold().reversed());
+        let mut state = ListState::default().with_selected(Some(search.selected));
+        frame.render_stateful_widget(hits, padded(hits_area, 1), &mut state);
+    }
+}
+
+fn help(frame: &mut Frame) {
+    let content = view::get_help_text()
+        .iter()
+        .map(|(key, desc)| Line::from(format!("{key}    {desc}")))
+        .collect::<Vec<_>>();
+    let content = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title(tr().help))
+        .cyan();
+    frame.render_widget(content, frame.size());
+}
+
+fn confirm_quit(frame: &mut Frame) {
+    let content = Paragraph::new(tr().confirm)
+        .block(Block::default().borders(Borders::ALL).title(tr().quit))
+        .cyan()
+        .centered();
+    frame.render_widget(content, frame.size());
+}
+
+fn padded(area: Rect, horizontal: u16) -> Rect {
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Length(horizontal),
+        Constraint::Fill(1),
+        Constraint::Length(horizontal),
+    ])
+    .areas(area);
+    area
+}
+
+/// Converts a terminal key into the iced key `handle_key` expects, so both front-ends share the same key bindings.
+fn to_iced_key(code: KeyCode) -> Option<Key<SmolStr>> {
+    let key = match code {
+        KeyCode::Char(' ') => Key::Named(Named::Space),
+        KeyCode::Char(c) => Key::Character(SmolStr::new(c.to_string())),
+        KeyCode::Up => Key::Named(Named::ArrowUp),
+        KeyCode::Down => Key::Named(Named::ArrowDown),
+        KeyCode::Left => Key::Named(Named::ArrowLeft),
+        KeyCode::Right => Key::Named(Named::ArrowRight),
+        KeyCode::Enter => Key::Named(Named::Enter),
+        KeyCode::Backspace => Key::Named(Named::Backspace),
+        KeyCode::Esc => Key::Named(Named::Escape),
+        _ => return None,
+    };
+    Some(key)
+}
@@ -1,104 +1,325 @@
+mod disc;
+mod icon;
+mod overlay;
 mod utils;
+mod veil;
 
-use std::{fs, sync::OnceLock};
+use std::sync::OnceLock;
 
-use crate::{config::Config, App, Lang, Message};
+use crate::{config::Config, i18n::tr, lyrics, App, Message, ViewMode, WINDOW_SIZE};
 use iced::{
     alignment::Horizontal,
-    widget::{button, column, image, row, text, Column, Container, Slider},
-    Alignment, Element, Length,
+    widget::{button, canvas, column, image, row, text, Column, Container, Row, Slider, Space},
+    Alignment, Color, Element, Font, Length, Size,
 };
 use iced_aw::floating_element;
 use iced_aw::floating_element::Anchor;
-use itertools::Itertools;
-use rodio::Source;
-
-const KEY_DESCRIPTION: &[(&str, &str)] = &[
-    ("\n● 模式/播放", "\n"),
-    ("h", "进入帮助页面"),
-    ("[p, space]", "播放/暂停"),
-    ("t", "切换语言(默认双语字幕, 每次切换至中文/日语/双语)"),
-    ("s", "切换播放速度"),
-    ("q", "关闭应用"),
-    ("\n\n● 模式/帮助", "\n"),
-    ("h", "退出帮助页面"),
-    ("\n\n● 模式/退出", "\n"),
-    ("y", "确认"),
-    ("n", "取消"),
-];
 
 const TITLE_SIZE: u16 = 36;
 const TITLE_PADDING: u16 = 20;
 const CONTENT_SIZE: u16 = 20;
 
-pub fn play(app: &App) -> Element<Message> {
-    let Config {
-        name,
-        avatar,
-        background,
-        ..
-    } = &app.config[app.current_pos];
+pub fn play(app: &App) -> Element<'_, Message> {
+    let colors = &app.themes.colors;
+    let layout = PlayLayout::new(app.window_size);
 
-    let background = utils::background_image(background);
+    let Config { name, .. } = &app.player.config[app.player.current_pos];
 
-    let avatar = image(avatar).width(400).height(400);
+    let background = animated_background(app);
+
+    let vinyl = &app.animations.vinyl;
+    let avatar: Element<Message> = match vinyl.label() {
+        Some(label) => disc::Disc::new(label, vinyl.angle, layout.avatar).into(),
+        None => Space::new(layout.avatar, layout.avatar).into(),
+    };
     let name = text(name)
-        .size(40)
+        .size(layout.scaled(40))
         // .width(Length::Fill)
         // .horizontal_alignment(Horizontal::Center)
-        .style(utils::text(utils::black()));
-    let status_line = status_line(app);
+        .style(utils::text(colors.text));
+    let status_line = status_line(app, layout.scaled(20));
 
-    let lyric = match app.lang {
-        Lang::All => {
-            let lyric_1 = get_lyrics(app, Lang::Chinese).unwrap_or_default();
-            let lyric_1 = text(lyric_1).size(30).style(utils::text(utils::black()));
+    let lyric = animated_lyrics(app, layout.scaled(30), layout.scaled(40), layout.scaled(20))
+        .align_items(Alignment::Center);
 
-            let lyric_2 = get_lyrics(app, Lang::Japanese).unwrap_or_default();
-            let lyric_2 = text(lyric_2).size(30).style(utils::text(utils::black()));
-            column!(lyric_1, lyric_2)
-        }
-        single_lang => {
-            let lyric = get_lyrics(app, single_lang).unwrap_or_default();
-            let lyric = text(lyric).size(30).style(utils::text(utils::black()));
-            column!(lyric)
+    let total_duration = get_total_duration(app);
+    let slider = Slider::new(
+        0.0..=total_duration,
+        app.player.slider_value,
+        Message::UpdateSlider,
+    )
+    .on_release(Message::SeekAudio)
+    .height(layout.scaled(15))
+    .width(layout.slider)
+    .style(utils::StyledSlider::new(colors));
+
+    let right = column!(name, status_line, slider, lyric)
+        .spacing(5)
+        .width(Length::Fill)
+        .align_items(Alignment::Center);
+
+    // let right = Container::new(right).center_x();
+
+    let container: Element<Message> = if layout.vertical {
+        column!(avatar, right)
+            .spacing(layout.scaled(40))
+            .align_items(Alignment::Center)
+            .into()
+    } else {
+        row!(avatar, right).align_items(Alignment::Center).into()
+    };
+    let container = Container::new(container)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding([0, layout.scaled(90)])
+        .center_x()
+        .center_y();
+    let error = app
+        .config_error
+        .as_ref()
+        .or(app.player.error.as_ref())
+        .or(app.player.lyrics.error.as_ref());
+    let container: Element<Message> = match error {
+        Some(error) => {
+            let error = text(error)
+                .size(layout.scaled(16))
+                .style(utils::text(colors.text));
+            column!(error, container).padding(layout.scaled(10)).into()
         }
+        None => container.into(),
     };
+    let container = floating_element(background, container).anchor(Anchor::NorthWest);
 
-    let lyric = lyric.padding(40).align_items(Alignment::Center);
+    container.into()
+}
+
+pub fn mini(app: &App) -> Element<'_, Message> {
+    let colors = &app.themes.colors;
+    const MINI_SIZE: u16 = 16;
+
+    let Config { name, avatar, .. } = &app.player.config[app.player.current_pos];
+
+    let background = animated_background(app);
+
+    let avatar = avatar_image(app, avatar, 80);
+    let name = text(name)
+        .size(MINI_SIZE + 4)
+        .style(utils::text(colors.text));
+
+    let (font, lyric) = lyric_lines(app)
+        .into_iter()
+        .find(|(_, lyric)| !lyric.is_empty())
+        .unwrap_or((app.fonts.ui, String::new()));
+    let lyric = text(lyrics::ruby::plain(&lyric))
+        .size(MINI_SIZE)
+        .font(font)
+        .style(utils::text(colors.text));
 
     let total_duration = get_total_duration(app);
     let slider = Slider::new(
         0.0..=total_duration,
-        app.slider_value,
+        app.player.slider_value,
         Message::UpdateSlider,
     )
     .on_release(Message::SeekAudio)
-    .height(15)
-    .width(600)
-    .style(utils::StyledSlider::new());
+    .height(10)
+    .style(utils::StyledSlider::new(colors));
 
-    let right = column!(name, status_line, slider, lyric)
-        .spacing(5)
+    let info = column!(name, lyric, slider).spacing(5).width(Length::Fill);
+    let controls = transport_buttons(app, MINI_SIZE * 2);
+
+    let container = row!(avatar, info, controls)
+        .spacing(20)
+        .padding(20)
+        .align_items(Alignment::Center);
+    let container = floating_element(background, container).anchor(Anchor::NorthWest);
+
+    container.into()
+}
+
+pub fn fullscreen(app: &App) -> Element<'_, Message> {
+    let colors = &app.themes.colors;
+    let layout = PlayLayout::new(app.window_size);
+
+    let Config { avatar, .. } = &app.player.config[app.player.current_pos];
+
+    let background = animated_background(app);
+
+    let lyric = animated_lyrics(app, layout.scaled(60), 0, layout.scaled(40))
+        .spacing(layout.scaled(20))
+        .align_items(Alignment::Center);
+    let lyric = Container::new(lyric)
         .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y();
+
+    let avatar = avatar_image(app, avatar, layout.scaled(160));
+    let mut corner = row!(avatar)
+        .spacing(layout.scaled(30))
         .align_items(Alignment::Center);
+    if app.controls_visible() {
+        let exit = button(text("✕").size(layout.scaled(40)))
+            .on_press(Message::SwitchView(ViewMode::Play))
+            .padding(0)
+            .style(utils::StyledButton::new(colors));
+        corner = corner
+            .push(transport_buttons(app, layout.scaled(40)))
+            .push(exit);
+    }
 
-    // let right = Container::new(right).center_x();
+    let container = column!(lyric, corner).padding(layout.scaled(40));
+    let container = floating_element(background, container).anchor(Anchor::NorthWest);
+
+    container.into()
+}
+
+pub fn lyric_overlay(app: &App) -> Element<'_, Message> {
+    let overlay = overlay::LyricOverlay {
+        lines: lyric_lines(app)
+            .into_iter()
+            .map(|(font, line)| {
+                let line = lyrics::ruby::inline(&line, app.player.show_readings);
+                (font, line)
+            })
+            .collect(),
+        config: &app.player.options.overlay,
+        locked: app.overlay_locked,
+        backdrop: app.themes.colors.overlay_backdrop,
+    };
+
+    canvas(overlay)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// The lines of the lyric editor around the next one to stamp, each after its
+/// timestamp once it has one.
+pub fn lyric_editor(app: &App) -> Element<'_, Message> {
+    const CONTEXT: usize = 6;
+    let colors = &app.themes.colors;
+    let Some(editor) = &app.player.editor else {
+        return play(app);
+    };
+    let Config { name, .. } = &app.player.config[app.player.current_pos];
+
+    let title = text(format!("{} - {name}", tr().lyric_editor))
+        .size(TITLE_SIZE)
+        .style(utils::text(colors.text));
+    let status_line = status_line(app, CONTENT_SIZE);
+    let path = text(editor.path.display().to_string())
+        .size(CONTENT_SIZE - 4)
+        .style(utils::text(colors.text));
+
+    let current = editor.current();
+    let mut lines = Column::new().spacing(8);
+    for (idx, line) in editor
+        .lines
+        .iter()
+        .enumerate()
+        .skip(current.saturating_sub(CONTEXT))
+        .take(CONTEXT * 2 + 1)
+    {
+        let time = match editor.times.get(idx) {
+            Some(time) => lyrics::format_time(time.as_secs_f32()),
+            None => "[--:--.--]".to_string(),
+        };
+        let color = if idx == current {
+            colors.lyric_active
+        } else {
+            colors.lyric_inactive
+        };
+        let line = text(format!("{time}  {line}"))
+            .size(CONTENT_SIZE + 4)
+            .font(app.fonts.lyric(editor.lang))
+            .style(utils::text(color));
+        lines = lines.push(line);
+    }
+
+    let mut content = column!(title, path, status_line)
+        .spacing(10)
+        .padding(TITLE_PADDING * 2);
+    if let Some(message) = &editor.message {
+        content = content.push(
+            text(message)
+                .size(CONTENT_SIZE)
+                .style(utils::text(colors.accent)),
+        );
+    }
+    let content = content.push(lines.padding([TITLE_PADDING, 0]));
+    let container = floating_element(animated_background(app), content).anchor(Anchor::NorthWest);
+
+    container.into()
+}
+
+/// The query of the lyric search and the lines found around the selected one, each
+/// after the name of its song and its time.
+pub fn search(app: &App) -> Element<'_, Message> {
+    const CONTEXT: usize = 6;
+    let colors = &app.themes.colors;
+    let Some(search) = &app.player.search else {
+        return play(app);
+    };
+
+    let title = text(tr().lyric_search)
+        .size(TITLE_SIZE)
+        .style(utils::text(colors.text));
+    let query = text(format!("> {}_", search.query))
+        .size(CONTENT_SIZE + 4)
+        .style(utils::text(colors.accent));
+    let mut content = column!(title, query).spacing(10).padding(TITLE_PADDING * 2);
+    if !search.query.trim().is_empty() && search.hits.is_empty() {
+        content = content.push(
+            text(tr().no_results)
+                .size(CONTENT_SIZE)
+                .style(utils::text(colors.text)),
+        );
+    }
+
+    let mut hits = Column::new().spacing(8);
+    for (idx, hit) in search
+        .hits
+        .iter()
+        .enumerate()
+        .skip(search.selected.saturating_sub(CONTEXT))
+        .take(CONTEXT * 2 + 1)
+    {
+        let color = if idx == search.selected {
+            colors.lyric_active
+        } else {
+            colors.lyric_inactive
+        };
+        // the playlist may have been reloaded since the search was opened
+        let name = app
+            .player
+            .config
+            .get(hit.song)
+            .map_or("", |song| song.name.as_str());
+        let line = text(format!(
+            "{name}  {}  {}",
+            lyrics::format_time(hit.time),
+            hit.line
+        ))
+        .size(CONTENT_SIZE + 4)
+        .font(app.fonts.lyric(hit.lang))
+        .style(utils::text(color));
+        hits = hits.push(line);
+    }
 
-    let container = row!(avatar, right);
-    let container = floating_element(background, container)
-        .anchor(Anchor::NorthWest)
-        .offset([90.0, 150.0]);
+    let content = content.push(hits.padding([TITLE_PADDING, 0]));
+    let container = floating_element(animated_background(app), content).anchor(Anchor::NorthWest);
 
     container.into()
 }
 
-pub fn help(_app: &App) -> Element<Message> {
-    let title = text("Help")
+pub fn help(app: &App) -> Element<'_, Message> {
+    let colors = &app.themes.colors;
+    let title = text(tr().help)
         .width(Length::Shrink)
         .size(TITLE_SIZE)
         .horizontal_alignment(Horizontal::Center)
-        .style(utils::text(utils::cyan()));
+        .style(utils::text(colors.accent));
     let title = row!(title).padding(TITLE_PADDING);
 
     let mut content = Column::new().padding(5);
@@ -107,7 +328,7 @@ pub fn help(_app: &App) -> Element<Message> {
             text(s)
                 .width(Length::Shrink)
                 .size(CONTENT_SIZE)
-                .style(utils::text(utils::cyan()))
+                .style(utils::text(colors.accent))
         };
         let (key, desc) = (to_text(key), to_text(desc));
         let row = row!(key, desc).spacing(50).padding(2);
@@ -121,18 +342,19 @@ pub fn help(_app: &App) -> Element<Message> {
     container.into()
 }
 
-pub fn confirm_quit(_app: &App) -> Element<Message> {
-    let title = text("Quit?")
+pub fn confirm_quit(app: &App) -> Element<'_, Message> {
+    let colors = &app.themes.colors;
+    let title = text(tr().quit)
         .width(Length::Shrink)
         .size(TITLE_SIZE)
         .horizontal_alignment(Horizontal::Center)
-        .style(utils::text(utils::cyan()));
+        .style(utils::text(colors.accent));
     let title = row!(title).padding(TITLE_PADDING);
 
-    let tips = text("Y / N")
+    let tips = text(tr().confirm)
         .width(Length::Shrink)
         .size(CONTENT_SIZE * 2)
-        .style(utils::text(utils::cyan()));
+        .style(utils::text(colors.accent));
     let tips = Container::new(tips).width(Length::Fill).center_x();
 
     let container = column!(title, tips).spacing(20);
@@ -141,58 +363,47 @@ pub fn confirm_quit(_app: &App) -> Element<Message> {
     container.into()
 }
 
-pub fn status_line(app: &App) -> Element<Message> {
-    const STATUS_LINE_SIZE: u16 = 20;
+pub fn status_line(app: &App, size: u16) -> Element<'_, Message> {
+    let colors = &app.themes.colors;
+    let tr = tr();
 
     // volume
-    let volume = format!("音量: {}%", (app.sink.volume() * 100.0) as i8);
-    let volume = text(volume)
-        .size(STATUS_LINE_SIZE)
-        .style(utils::text(utils::black()));
+    let volume = format!(
+        "{}: {}%",
+        tr.volume,
+        (app.player.sink.volume() * 100.0) as i8
+    );
+    let volume = text(volume).size(size).style(utils::text(colors.text));
 
     // time
-    let time = {
-        let get_time = |secs| (secs / 60, secs % 60);
-
-        let (minute, second) = get_time(app.time.as_secs());
-        let (total_minute, total_second) =
-            get_time(app.current_source.total_duration().unwrap().as_secs());
-
-        let time = if minute != 0 {
-            format!(
-                "已播放: {}m{}s/{}m{}s",
-                minute, second, total_minute, total_second,
-            )
-        } else {
-            format!("已播放: {}s/{}m{}s", second, total_minute, total_second,)
-        };
-
-        text(time)
-            .size(STATUS_LINE_SIZE)
-            .style(utils::text(utils::black()))
-    };
+    let time = text(app.player.time_text())
+        .size(size)
+        .style(utils::text(colors.text));
 
     // is_paused
-    let is_paused = text(if app.sink.is_paused() {
-        "暂停中"
-    } else {
-        "播放中"
-    })
-    .size(STATUS_LINE_SIZE)
-    .style(utils::text(utils::black()));
+    let is_paused = text(tr.play_state(app.player.sink.is_paused()))
+        .size(size)
+        .style(utils::text(colors.text));
 
     // speed
-    let speed = text(format!("速度: {:.1}", app.sink.speed())).size(STATUS_LINE_SIZE);
+    let speed = text(format!("{}: {:.1}", tr.speed, app.player.sink.speed())).size(size);
     let speed = button(speed)
         .on_press(Message::ToggleSpeed)
         .padding(0)
-        .style(utils::StyledButton::new());
+        .style(utils::StyledButton::new(colors));
     // let speed = Container::new(speed).
 
-    row!(time, volume, speed, is_paused).spacing(30).into()
+    // lyric offset
+    let offset = text(app.player.offset_text())
+        .size(size)
+        .style(utils::text(colors.text));
+
+    row!(time, volume, speed, offset, is_paused)
+        .spacing(size * 3 / 2)
+        .into()
 }
 
-fn get_help_text() -> &'static Vec<(String, String)> {
+pub fn get_help_text() -> &'static Vec<(String, String)> {
     static KEY_DESCRIPTION_CACHE: OnceLock<Vec<(String, String)>> = OnceLock::new();
 
     KEY_DESCRIPTION_CACHE.get_or_init(|| {
@@ -207,12 +418,13 @@ fn get_help_text() -> &'static Vec<(String, String)> {
         };
 
         let (mut key_max_len, mut desc_max_len) = (0, 0);
-        for (key, desc) in KEY_DESCRIPTION {
+        let key_description = tr().key_description;
+        for (key, desc) in key_description {
             key_max_len = get_len(key).max(key_max_len);
             desc_max_len = get_len(desc).max(desc_max_len);
         }
 
-        KEY_DESCRIPTION
+        key_description
             .iter()
             .map(|(key, desc)| {
                 let key = get_format(key, key_max_len);
@@ -223,42 +435,142 @@ fn get_help_text() -> &'static Vec<(String, String)> {
     })
 }
 
-fn get_total_duration(app: &App) -> f32 {
-    app.current_source.total_duration().unwrap().as_secs_f32()
+fn transport_buttons(app: &App, size: u16) -> Element<'_, Message> {
+    let colors = &app.themes.colors;
+    let to_button = |label, msg| {
+        button(text(label).size(size))
+            .on_press(msg)
+            .padding(0)
+            .style(utils::StyledButton::new(colors))
+    };
+    let play = icon::PlayIcon {
+        progress: app.animations.play_icon.value(),
+        color: colors.button,
+    };
+    let play = canvas(play).width(size).height(size);
+    let next = to_button("⏭", Message::NextSong);
+
+    row!(play, next).spacing(size / 2).into()
 }
 
-fn get_lyrics(app: &App, lang: Lang) -> Option<String> {
-    let config = &app.config;
-    let pos = app.current_pos;
+/// Sizes of the play view, scaled from the layout designed for the default
+/// window size and switched to a vertical layout for narrow windows.
+struct PlayLayout {
+    scale: f32,
+    vertical: bool,
+    avatar: u16,
+    slider: u16,
+}
 
-    let path = match lang {
-        Lang::Chinese => config[pos].lyrics_first.as_ref(),
-        Lang::Japanese => config[pos].lyrics_second.as_ref(),
-        _ => unimplemented!(),
-    };
+impl PlayLayout {
+    fn new(window: Size) -> Self {
+        let vertical = window.width < window.height;
 
-    let path = path.unwrap();
-    let lyric = fs::read_to_string(path).unwrap();
-    let duration = app.time.as_secs_f32();
+        let design = if vertical {
+            Size::new(WINDOW_SIZE.height, WINDOW_SIZE.width)
+        } else {
+            WINDOW_SIZE
+        };
+        let scale = (window.width / design.width)
+            .min(window.height / design.height)
+            .max(0.3);
+
+        Self {
+            scale,
+            vertical,
+            avatar: (400.0 * scale) as u16,
+            slider: (600.0 * scale) as u16,
+        }
+    }
 
-    let mut s = "";
-    let result = lyric.lines().rev().find(|line| {
-        let pos = line.chars().positions(|v| "[:]".contains(v)).collect_vec();
+    fn scaled(&self, size: u16) -> u16 {
+        (size as f32 * self.scale).round() as u16
+    }
+}
 
-        if pos.len() < 3 || !(line[1..=1].chars().collect_vec()[0]).is_ascii_digit() {
-            return false;
+/// The background of the current song, dimmed by the theme, which the one of the
+/// previous song fades into while the song changes.
+fn animated_background(app: &App) -> Element<'_, Message> {
+    let backdrop = &app.animations.backdrop;
+    let background = match backdrop.frame() {
+        Some(frame) => utils::background_image(frame).into(),
+        None => background_image(app, backdrop.background()),
+    };
+    veil::Veil::new(background, app.themes.colors.background_dim).into()
+}
+
+/// The current lyric lines, fading in while sliding up by `slide` whenever they
+/// change. The padding moves from the top to the bottom, so the height stays the same.
+fn animated_lyrics(app: &App, size: u16, padding: u16, slide: u16) -> Column<'_, Message> {
+    let progress = app.animations.lyric.value();
+    let color = utils::mix(
+        app.themes.colors.lyric_inactive,
+        app.themes.colors.lyric_active,
+        progress,
+    );
+
+    let lyric = lyric_lines(app).into_iter().map(|(font, lyric)| {
+        if app.player.show_readings && lyrics::ruby::has_readings(&lyric) {
+            return ruby_line(&lyric, size, font, color);
         }
+        text(lyrics::ruby::plain(&lyric))
+            .size(size)
+            .font(font)
+            .style(utils::text(color))
+            .into()
+    });
+    let (padding, offset) = (padding as f32, (1.0 - progress) * slide as f32);
+
+    Column::with_children(lyric).padding([
+        padding + offset,
+        padding,
+        padding + slide as f32 - offset,
+        padding,
+    ])
+}
 
-        let minute = &line[(pos[0] + 1)..pos[1]];
-        let second = &line[(pos[1] + 1)..pos[2]];
-        s = &line[(pos[2] + 1)..];
+/// A lyric line with the readings of its ruby annotations above their base text.
+fn ruby_line<'a>(line: &str, size: u16, font: Font, color: Color) -> Element<'a, Message> {
+    let segments = lyrics::ruby::segments(line)
+        .into_iter()
+        .map(|(base, reading)| {
+            // an empty reading keeps the base text on the same baseline
+            let reading = text(reading.unwrap_or_default())
+                .size(size / 2)
+                .font(font)
+                .style(utils::text(color));
+            let base = text(base).size(size).font(font).style(utils::text(color));
+            column!(reading, base).align_items(Alignment::Center).into()
+        });
+    Row::with_children(segments).into()
+}
 
-        let time = minute
-            .parse::<f32>()
-            .and_then(|m| second.parse::<f32>().map(|s| m * 60.0 + s))
-            .ok();
+/// The current lyric lines, each with the font of its language.
+fn lyric_lines(app: &App) -> Vec<(Font, String)> {
+    let fonts = app
+        .player
+        .langs()
+        .into_iter()
+        .map(|lang| app.fonts.lyric(lang));
+    fonts.zip(app.player.current_lyrics()).collect()
+}
 
-        time.is_some() && !s.is_empty() && duration >= time.unwrap()
-    });
-    result.map(|_| s.to_string().replace(['，', '。'], " "))
+fn background_image<'a>(app: &App, background: &Option<String>) -> Element<'a, Message> {
+    match background {
+        Some(background) => utils::background_image(app.image(background)).into(),
+        None => Space::new(Length::Fill, Length::Fill).into(),
+    }
+}
+
+fn avatar_image<'a>(app: &App, avatar: &Option<String>, size: u16) -> Element<'a, Message> {
+    match avatar {
+        Some(avatar) => image(app.image(avatar)).width(size).height(size).into(),
+        None => Space::new(size, size).into(),
+    }
+}
+
+/// The end of the slider, which follows the clock when the duration is unknown.
+fn get_total_duration(app: &App) -> f32 {
+    let player = &app.player;
+    player.total_duration().unwrap_or(player.time).as_secs_f32()
 }
@@ -0,0 +1,130 @@
+use std::f32::consts::PI;
+
+use iced::{
+    advanced::{
+        graphics::geometry,
+        image, layout,
+        renderer::{self, Renderer as _},
+        widget::Tree,
+        Layout, Widget,
+    },
+    mouse,
+    widget::canvas::{path::Arc, Frame, Path, Stroke},
+    Color, Element, Length, Point, Radians, Rectangle, Renderer, Size, Theme, Vector,
+};
+
+use crate::animation::LABEL_RADIUS;
+
+const VINYL: Color = Color::from_rgb(0.07, 0.07, 0.07);
+const GROOVE: Color = Color::from_rgb(0.11, 0.11, 0.11);
+const SHEEN: Color = Color::from_rgba(1.0, 1.0, 1.0, 0.07);
+/// Distance between two grooves, in parts of the radius.
+const GROOVE_GAP: f32 = 0.035;
+const SHEEN_ANGLE: f32 = 0.45;
+
+/// The vinyl record with `label` on it, turned by `angle`.
+///
+/// The record is drawn as geometry rotated by the transform of its frame. Images
+/// cannot be rotated that way, so the label comes already turned by `angle`.
+pub struct Disc {
+    label: image::Handle,
+    angle: f32,
+    size: f32,
+}
+
+impl Disc {
+    pub fn new(label: image::Handle, angle: f32, size: impl Into<f32>) -> Self {
+        Self {
+            label,
+            angle,
+            size: size.into(),
+        }
+    }
+
+    fn record(&self, renderer: &Renderer) -> Frame {
+        let mut frame = Frame::new(renderer, Size::new(self.size, self.size));
+        let radius = self.size / 2.0;
+        frame.translate(Vector::new(radius, radius));
+        frame.rotate(self.angle);
+
+        frame.fill(&Path::circle(Point::ORIGIN, radius), VINYL);
+        let grooves = Path::new(|builder| {
+            let mut groove = LABEL_RADIUS + GROOVE_GAP;
+            while groove < 1.0 - GROOVE_GAP / 2.0 {
+                builder.circle(Point::ORIGIN, radius * groove);
+                groove += GROOVE_GAP;
+            }
+        });
+        frame.stroke(&grooves, Stroke::default().with_color(GROOVE));
+
+        // two reflections on opposite sides, which show the record turning
+        for start in [0.0, PI] {
+            let sheen = Path::new(|builder| {
+                builder.arc(Arc {
+                    center: Point::ORIGIN,
+                    radius,
+                    start_angle: Radians(start),
+                    end_angle: Radians(start + SHEEN_ANGLE),
+                });
+                builder.line_to(Point::ORIGIN);
+                builder.close();
+            });
+            frame.fill(&sheen, SHEEN);
+        }
+
+        frame
+    }
+}
+
+impl<Message> Widget<Message, Theme, Renderer> for Disc {
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fixed(self.size), Length::Fixed(self.size))
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::Node::new(limits.resolve(self.size, self.size, Size::new(self.size, self.size)))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let record = self.record(renderer).into_geometry();
+        renderer.with_translation(Vector::new(bounds.x, bounds.y), |renderer| {
+            geometry::Renderer::draw(renderer, vec![record]);
+        });
+
+        // images go above the geometry of the same layer
+        let side = self.size * LABEL_RADIUS;
+        let label = Rectangle {
+            x: bounds.center_x() - side / 2.0,
+            y: bounds.center_y() - side / 2.0,
+            width: side,
+            height: side,
+        };
+        image::Renderer::draw(
+            renderer,
+            self.label.clone(),
+            image::FilterMethod::Linear,
+            label,
+        );
+    }
+}
+
+impl<'a, Message: 'a> From<Disc> for Element<'a, Message> {
+    fn from(disc: Disc) -> Self {
+        Self::new(disc)
+    }
+}
@@ -0,0 +1,89 @@
+use iced::{
+    mouse,
+    widget::canvas::{event, Event, Frame, Geometry, Path, Program},
+    Color, Point, Rectangle, Renderer, Theme,
+};
+
+use crate::Message;
+
+type Shape = [[(f32, f32); 4]; 2];
+
+/// The two halves of the "play" triangle, in units of the icon size.
+const PLAY: Shape = [
+    [(0.2, 0.1), (0.55, 0.275), (0.55, 0.725), (0.2, 0.9)],
+    [(0.55, 0.275), (0.9, 0.5), (0.9, 0.5), (0.55, 0.725)],
+];
+/// The two bars of the "pause" icon, matching the corners of [`PLAY`].
+const PAUSE: Shape = [
+    [(0.2, 0.1), (0.4, 0.1), (0.4, 0.9), (0.2, 0.9)],
+    [(0.6, 0.1), (0.8, 0.1), (0.8, 0.9), (0.6, 0.9)],
+];
+
+/// The play/pause button, morphing from the triangle (`progress` 0) into the
+/// two bars (`progress` 1).
+pub struct PlayIcon {
+    pub progress: f32,
+    pub color: Color,
+}
+
+impl Program<Message> for PlayIcon {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if cursor.is_over(bounds) =>
+            {
+                (event::Status::Captured, Some(Message::TogglePlay))
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let size = bounds.width.min(bounds.height);
+        let lerp = |(x0, y0): (f32, f32), (x1, y1): (f32, f32)| {
+            let t = self.progress;
+            Point::new((x0 + (x1 - x0) * t) * size, (y0 + (y1 - y0) * t) * size)
+        };
+
+        for (play, pause) in PLAY.iter().zip(PAUSE.iter()) {
+            let path = Path::new(|builder| {
+                let mut corners = play.iter().zip(pause).map(|(&a, &b)| lerp(a, b));
+                builder.move_to(corners.next().unwrap());
+                corners.for_each(|corner| builder.line_to(corner));
+                builder.close();
+            });
+            frame.fill(&path, self.color);
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
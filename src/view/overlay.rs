@@ -0,0 +1,120 @@
+use iced::{
+    alignment::{Horizontal, Vertical},
+    mouse,
+    widget::canvas::{event, Event, Frame, Geometry, Program, Text},
+    widget::text::Shaping,
+    Color, Font, Pixels, Point, Rectangle, Renderer, Theme, Vector,
+};
+
+use crate::{config::Overlay, theme, Message};
+
+const OUTLINE_OFFSETS: &[(f32, f32)] = &[
+    (-1.0, -1.0),
+    (0.0, -1.0),
+    (1.0, -1.0),
+    (-1.0, 0.0),
+    (1.0, 0.0),
+    (-1.0, 1.0),
+    (0.0, 1.0),
+    (1.0, 1.0),
+];
+
+/// Draws the lyric lines centered on a transparent canvas, outlined so they stay
+/// readable on any desktop background. While unlocked, dragging it moves the window.
+pub struct LyricOverlay<'a> {
+    pub lines: Vec<(Font, String)>,
+    pub config: &'a Overlay,
+    pub locked: bool,
+    pub backdrop: Color,
+}
+
+impl Program<Message> for LyricOverlay<'_> {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if !self.locked && cursor.is_over(bounds) =>
+            {
+                (event::Status::Captured, Some(Message::DragOverlay))
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        // a faint backdrop shows where the window is while it can be moved
+        if !self.locked {
+            frame.fill_rectangle(Point::ORIGIN, bounds.size(), self.backdrop);
+        }
+
+        let color = theme::hex(&self.config.color).unwrap_or(Color::WHITE);
+        let outline_color = theme::hex(&self.config.outline_color).unwrap_or(Color::BLACK);
+        let outline_width = self.config.outline_width;
+        let size = self.config.font_size as f32;
+        let line_height = size * 1.4;
+
+        let lines = self
+            .lines
+            .iter()
+            .filter(|(_, line)| !line.is_empty())
+            .collect::<Vec<_>>();
+        let top = (bounds.height - line_height * lines.len() as f32) / 2.0;
+
+        for (idx, (font, line)) in lines.into_iter().enumerate() {
+            let position = Point::new(bounds.width / 2.0, top + line_height * (idx as f32 + 0.5));
+            let text = Text {
+                content: line.clone(),
+                position,
+                color,
+                size: Pixels(size),
+                font: *font,
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment: Vertical::Center,
+                shaping: Shaping::Advanced,
+                ..Text::default()
+            };
+
+            if outline_width > 0.0 {
+                for (x, y) in OUTLINE_OFFSETS {
+                    frame.fill_text(Text {
+                        position: position + Vector::new(x * outline_width, y * outline_width),
+                        color: outline_color,
+                        ..text.clone()
+                    });
+                }
+            }
+            frame.fill_text(text);
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if !self.locked && cursor.is_over(bounds) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
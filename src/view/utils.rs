@@ -7,6 +7,8 @@ use iced::{
     Border, Color, ContentFit, Length, Shadow, Theme, Vector,
 };
 
+use crate::theme::Colors;
+
 pub fn background_image<Handle>(handle: impl Into<Handle>) -> Image<Handle> {
     image(handle)
         .width(Length::Fill)
@@ -18,6 +20,17 @@ pub fn text(color: Color) -> Text {
     Text::Color(color)
 }
 
+/// Blends `from` into `to`, `t` going from 0 to 1.
+pub fn mix(from: Color, to: Color, t: f32) -> Color {
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+    Color::from_rgba(
+        lerp(from.r, to.r),
+        lerp(from.g, to.g),
+        lerp(from.b, to.b),
+        lerp(from.a, to.a),
+    )
+}
+
 pub fn transparent() -> Color {
     Color::from_rgba8(0, 0, 0, 0.0)
 }
@@ -38,14 +51,14 @@ pub fn blue() -> Color {
     Color::from_rgb8(3, 138, 255)
 }
 
-pub struct StyledSlider;
+pub struct StyledSlider(Colors);
 impl slider::StyleSheet for StyledSlider {
     type Style = Theme;
 
     fn active(&self, style: &Self::Style) -> slider::Appearance {
         slider::Appearance {
             rail: slider::Rail {
-                colors: (black(), blue()),
+                colors: (self.0.slider_progress, self.0.slider_rail),
                 width: 3.0,
                 border_radius: Radius::from(0.0),
             },
@@ -54,9 +67,9 @@ impl slider::StyleSheet for StyledSlider {
                     width: 10,
                     border_radius: Radius::from(1000.0),
                 },
-                color: white(),
+                color: self.0.slider_handle,
                 border_width: 1.0,
-                border_color: black(),
+                border_color: self.0.slider_handle_border,
             },
         }
     }
@@ -70,14 +83,14 @@ impl slider::StyleSheet for StyledSlider {
     }
 }
 
-pub struct StyledButton;
+pub struct StyledButton(Colors);
 impl button::StyleSheet for StyledButton {
     type Style = Theme;
 
     fn active(&self, style: &Self::Style) -> button::Appearance {
         button::Appearance {
             background: Some(iced::Background::Color(transparent())),
-            text_color: black(),
+            text_color: self.0.button,
             ..Default::default()
         }
     }
@@ -88,8 +101,8 @@ macro_rules! impl_new {
         paste::paste! {
             #[allow(clippy::new_ret_no_self)]
             impl [<Styled $t>] {
-                pub fn new() -> iced::theme::$t {
-                    iced::theme::$t::Custom(Box::new(Self))
+                pub fn new(colors: &Colors) -> iced::theme::$t {
+                    iced::theme::$t::Custom(Box::new(Self(colors.clone())))
                 }
             }
         }
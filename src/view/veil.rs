@@ -0,0 +1,105 @@
+use iced::{
+    advanced::{
+        layout, renderer,
+        widget::{tree, Tree},
+        Layout, Widget,
+    },
+    mouse, Color, Element, Length, Rectangle, Size,
+};
+
+/// Draws `color` over `content`, used to dim the background.
+///
+/// The color has to go on a layer of its own, as quads are otherwise drawn
+/// below the images of the same layer.
+pub struct Veil<'a, Message, Theme, Renderer> {
+    content: Element<'a, Message, Theme, Renderer>,
+    color: Color,
+}
+
+impl<'a, Message, Theme, Renderer> Veil<'a, Message, Theme, Renderer> {
+    pub fn new(content: impl Into<Element<'a, Message, Theme, Renderer>>, color: Color) -> Self {
+        Self {
+            content: content.into(),
+            color,
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for Veil<'a, Message, Theme, Renderer>
+where
+    Renderer: renderer::Renderer,
+{
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::stateless()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+
+        if self.color.a > 0.0 {
+            let bounds = layout.bounds();
+            renderer.with_layer(bounds, |renderer| {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds,
+                        ..Default::default()
+                    },
+                    self.color,
+                );
+            });
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<Veil<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: renderer::Renderer + 'a,
+{
+    fn from(veil: Veil<'a, Message, Theme, Renderer>) -> Self {
+        Element::new(veil)
+    }
+}
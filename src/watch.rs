@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+
+use iced::{
+    futures::{channel::mpsc, future, SinkExt, StreamExt},
+    subscription, Subscription,
+};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::Message;
+
+/// Reports the files changed in `dirs`. The directories are watched rather than the
+/// files themselves, as editors often save by replacing the file.
+pub fn subscription(dirs: Vec<PathBuf>) -> Subscription<Message> {
+    subscription::channel(dirs.clone(), 16, move |mut output| async move {
+        let (tx, mut rx) = mpsc::unbounded();
+        let watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                    let _ = tx.unbounded_send(event.paths);
+                }
+                _ => (),
+            });
+
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                eprintln!("{err}");
+                return future::pending().await;
+            }
+        };
+        for dir in &dirs {
+            if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                eprintln!("{}: {err}", dir.display());
+            }
+        }
+
+        loop {
+            let paths = rx.select_next_some().await;
+            let _ = output.send(Message::FilesChanged(paths)).await;
+        }
+    })
+}
+
+/// The directories to watch for `files`.
+pub fn dirs<'a>(files: impl IntoIterator<Item = &'a str>) -> Vec<PathBuf> {
+    let mut dirs = files
+        .into_iter()
+        .filter_map(|file| normalize(Path::new(file)).parent().map(Path::to_path_buf))
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Makes `path` comparable with the paths of the watcher events, which are made
+/// of the watched directory and the file name.
+pub fn normalize(path: &Path) -> PathBuf {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+
+    match path.file_name() {
+        Some(name) => dir.join(name),
+        None => dir,
+    }
+}